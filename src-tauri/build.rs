@@ -1,3 +1,5 @@
 fn main() {
+    // Sans la fonctionnalité "desktop" (CLI seule), rien à générer pour Tauri
+    #[cfg(feature = "desktop")]
     tauri_build::build()
 }
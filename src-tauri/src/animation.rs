@@ -0,0 +1,202 @@
+/*
+ * Copyright (C) 2026  Romain Lathuiliere
+ * * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ */
+
+// Images animées (GIF, WebP) : décodage image par image et réencodage en WebP animé.
+// L'AVIF animé n'est ni lu (le décodeur `image` ne lit que l'image principale) ni écrit
+// (ravif n'encode que des images fixes) : ces deux cas sont refusés plutôt qu'aplatis.
+// En JPEG, PNG ou JPEG XL, seule la première image est gardée, avec un avertissement.
+
+use image::codecs::gif::GifDecoder;
+use image::codecs::webp::WebPDecoder;
+use image::{AnimationDecoder, DynamicImage, Frame, ImageFormat, ImageReader, RgbaImage};
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::decode::is_avif_sequence;
+use crate::encoders::encode_webp_animation;
+use crate::engine::{resize_to_fit, Options};
+use crate::error::{Error, Result};
+use crate::format::OutputFormat;
+use crate::search::Encoded;
+
+/// Animation décodée : images à la taille du canevas, déjà composées.
+pub(crate) struct Animation {
+    frames: Vec<RgbaImage>,
+    delays_ms: Vec<u32>,
+    // 0 = boucle infinie
+    loop_count: u16,
+}
+
+impl Animation {
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.frames.first().map(|f| f.dimensions()).unwrap_or_default()
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+/// Formats de sortie qui gardent toutes les images d'une animation.
+pub(crate) fn handles_animation(format: OutputFormat) -> bool {
+    matches!(format, OutputFormat::Webp | OutputFormat::Auto)
+}
+
+/// Avertissement reporté quand une animation est aplatie en image fixe.
+pub(crate) fn frame_loss_warning(frame_count: usize, format: OutputFormat) -> String {
+    format!(
+        "animated input ({} frames): only the first frame was kept, {} output is not animated (use webp)",
+        frame_count, format
+    )
+}
+
+/// Refuse ce qui ferait perdre une animation AVIF : AVIF animé en entrée, ou animation
+/// (`frame_count` > 1) exportée en AVIF.
+pub(crate) fn check_animation_support(path: &Path, frame_count: usize, format: OutputFormat) -> Result<()> {
+    if is_avif_sequence(path) {
+        return Err(Error::UnsupportedFormat { details: "animated AVIF input is not supported".to_string() });
+    }
+    if frame_count > 1 && format == OutputFormat::Avif {
+        return Err(Error::UnsupportedFormat {
+            details: format!(
+                "animated input ({} frames): animated AVIF output is not supported, use webp",
+                frame_count
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Nombre d'images d'un GIF ou d'un WebP, sans décoder les pixels (1 pour une image fixe).
+/// Une erreur de lecture arrête le compte : le décodage dira ce qu'il en est.
+pub(crate) fn frame_count(path: &Path) -> usize {
+    let info = match guessed_format(path) {
+        Some(ImageFormat::Gif) => gif_info(path),
+        Some(ImageFormat::WebP) => webp_info(path),
+        _ => return 1,
+    };
+    info.map_or(1, |(frames, _)| frames.max(1))
+}
+
+fn guessed_format(path: &Path) -> Option<ImageFormat> {
+    ImageReader::open(path).ok()?.with_guessed_format().ok()?.format()
+}
+
+/// Décode les images d'un GIF ou d'un WebP animé. Une image illisible arrête le décodage,
+/// les précédentes sont gardées. `None` s'il en reste moins de deux.
+pub(crate) fn decode_animation(path: &Path) -> Result<Option<Animation>> {
+    let (frames, loop_count): (Vec<Frame>, u16) = match guessed_format(path) {
+        Some(ImageFormat::Gif) => {
+            let decoder = GifDecoder::new(BufReader::new(File::open(path)?)).map_err(Error::decode)?;
+            let frames = decoder.into_frames().map_while(|frame| frame.ok()).collect();
+            (frames, gif_info(path)?.1)
+        }
+        Some(ImageFormat::WebP) => {
+            let decoder = WebPDecoder::new(BufReader::new(File::open(path)?)).map_err(Error::decode)?;
+            if !decoder.has_animation() {
+                return Ok(None);
+            }
+            let frames = decoder.into_frames().map_while(|frame| frame.ok()).collect();
+            (frames, webp_info(path)?.1)
+        }
+        _ => return Ok(None),
+    };
+
+    if frames.len() < 2 {
+        return Ok(None);
+    }
+    let delays_ms = frames.iter().map(frame_delay_ms).collect();
+    let frames = frames.into_iter().map(Frame::into_buffer).collect();
+    Ok(Some(Animation { frames, delays_ms, loop_count }))
+}
+
+fn frame_delay_ms(frame: &Frame) -> u32 {
+    let (numer, denom) = frame.delay().numer_denom_ms();
+    if denom == 0 {
+        0
+    } else {
+        numer / denom
+    }
+}
+
+// Nombre d'images et de boucles, que le décodeur `image` ne donne pas. Seuls les en-têtes
+// d'images sont parcourus, sans tampon de pixels.
+fn gif_info(path: &Path) -> Result<(usize, u16)> {
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::Indexed);
+    let mut decoder = options
+        .read_info(BufReader::new(File::open(path)?))
+        .map_err(|e| Error::Decode { details: e.to_string() })?;
+    let mut frames = 0;
+    while let Ok(Some(_)) = decoder.next_frame_info() {
+        frames += 1;
+    }
+    // L'extension NETSCAPE (qui porte le nombre de boucles) a été lue au passage
+    let loop_count = match decoder.repeat() {
+        gif::Repeat::Infinite => 0,
+        gif::Repeat::Finite(count) => count,
+    };
+    Ok((frames, loop_count))
+}
+
+// Le conteneur WebP annonce ses images (ANMF) et ses boucles dans ses en-têtes
+fn webp_info(path: &Path) -> Result<(usize, u16)> {
+    let decoder = image_webp::WebPDecoder::new(BufReader::new(File::open(path)?))
+        .map_err(|e| Error::Decode { details: e.to_string() })?;
+    if !decoder.is_animated() {
+        return Ok((1, 0));
+    }
+    let loop_count = match decoder.loop_count() {
+        image_webp::LoopCount::Forever => 0,
+        image_webp::LoopCount::Times(count) => count.get(),
+    };
+    Ok((decoder.num_frames() as usize, loop_count))
+}
+
+/// Redimensionne chaque image puis encode l'animation en WebP. Les réglages de qualité,
+/// sans perte et WebP s'appliquent à toutes les images.
+pub(crate) fn encode_animation(animation: Animation, options: &Options) -> Result<Encoded> {
+    let reason = match options.format {
+        OutputFormat::Webp => None,
+        OutputFormat::Auto => Some("animated input: webp is the only animated output".to_string()),
+        other => {
+            return Err(Error::UnsupportedFormat { details: format!("animated {} output is not supported, use webp", other) })
+        }
+    };
+    if options.target_size.is_some() || options.min_ssim.is_some() {
+        return Err(Error::UnsupportedFormat {
+            details: "target size and min SSIM are not supported for animated images".to_string(),
+        });
+    }
+
+    let frames: Vec<RgbaImage> = animation
+        .frames
+        .into_par_iter()
+        .map(|frame| resize_to_fit(DynamicImage::ImageRgba8(frame), options.max_width, options.max_height).to_rgba8())
+        .collect();
+    let data = encode_webp_animation(
+        &frames,
+        &animation.delays_ms,
+        animation.loop_count,
+        options.quality,
+        options.lossless,
+        &options.webp,
+    )?;
+
+    // La première image sert de référence pour les dimensions de sortie
+    let first = frames.into_iter().next().ok_or_else(|| Error::encode("webp", "no frames"))?;
+    Ok(Encoded {
+        image: DynamicImage::ImageRgba8(first),
+        data,
+        quality: options.quality,
+        format: OutputFormat::Webp,
+        reason,
+    })
+}
@@ -0,0 +1,102 @@
+/*
+ * Copyright (C) 2026  Romain Lathuiliere
+ * * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ */
+
+// Format automatique : on encode plusieurs candidats et on garde le plus petit.
+// Pour comparer à qualité visuelle équivalente, chaque candidat vise le même SSIM :
+// celui demandé par l'utilisateur, sinon celui d'un encodage de référence à `quality`.
+
+use image::DynamicImage;
+
+use crate::engine::{encode_image, Options};
+use crate::error::{Error, Result};
+use crate::format::OutputFormat;
+use crate::search::{encoded_ssim, fit_to_size, fit_to_ssim, Encoded};
+
+// Ordre d'essai ; à taille égale, le premier l'emporte
+const CANDIDATES: [OutputFormat; 4] = [OutputFormat::Avif, OutputFormat::Webp, OutputFormat::Png, OutputFormat::Jpg];
+
+/// Encode `img` dans chaque format candidat et retient la sortie la plus légère.
+pub(crate) fn encode_best(img: DynamicImage, options: &Options) -> Result<Encoded> {
+    let transparent = has_transparency(&img);
+    let mut notes = Vec::new();
+
+    let candidates: Vec<OutputFormat> = CANDIDATES
+        .into_iter()
+        .filter(|&format| {
+            let excluded = match format {
+                OutputFormat::Jpg if transparent => Some("transparency"),
+                OutputFormat::Jpg if options.lossless => Some("no lossless mode"),
+                _ => None,
+            };
+            if let Some(why) = excluded {
+                notes.push(format!("{} excluded ({})", format, why));
+            }
+            excluded.is_none()
+        })
+        .collect();
+
+    // Seuil commun : SSIM demandé, sinon celui de la référence (JPEG, ou WebP si transparence)
+    let (criterion, min_ssim) = if options.lossless {
+        ("lossless".to_string(), None)
+    } else if let Some(budget) = options.target_size {
+        (format!("under {} bytes", budget), None)
+    } else {
+        let threshold = match options.min_ssim {
+            Some(min_ssim) => min_ssim,
+            None => {
+                let reference = if transparent { OutputFormat::Webp } else { OutputFormat::Jpg };
+                let data = encode_image(&img, &candidate_options(options, reference))?;
+                encoded_ssim(&img, &data, reference)?
+            }
+        };
+        (format!("at SSIM >= {:.3}", threshold), Some(threshold))
+    };
+
+    // Meilleur candidat et seuil SSIM atteint ou non (toujours vrai hors mode SSIM)
+    let mut best: Option<(Encoded, bool)> = None;
+    for format in candidates {
+        let opts = candidate_options(options, format);
+        let result = match (options.target_size, min_ssim) {
+            (Some(budget), _) => fit_to_size(img.clone(), &opts, budget).map(|encoded| (encoded, true)),
+            (None, Some(threshold)) => fit_to_ssim(img.clone(), &opts, threshold),
+            (None, None) => encode_image(&img, &opts).map(|data| (Encoded::new(img.clone(), data, &opts), true)),
+        };
+        match result {
+            Ok((encoded, met)) => {
+                let missed = if met { "" } else { ", below threshold" };
+                notes.push(format!("{} {} B (q{}{})", format, encoded.data.len(), encoded.quality, missed));
+                // Un candidat sous le seuil n'est pas équivalent : il ne gagne que si aucun ne l'atteint
+                let better = best.as_ref().is_none_or(|(b, b_met)| {
+                    (met && !b_met) || (met == *b_met && encoded.data.len() < b.data.len())
+                });
+                if better {
+                    best = Some((encoded, met));
+                }
+            }
+            Err(e) => notes.push(format!("{} failed ({})", format, e)),
+        }
+    }
+
+    let (mut best, met) =
+        best.ok_or_else(|| Error::encode("auto", format!("no candidate succeeded: {}", notes.join(", "))))?;
+    best.reason = Some(if met {
+        format!("{} is the smallest {}: {}", best.format, criterion, notes.join(", "))
+    } else {
+        format!("no candidate {}, {} is the smallest: {}", criterion, best.format, notes.join(", "))
+    });
+    Ok(best)
+}
+
+fn candidate_options(options: &Options, format: OutputFormat) -> Options {
+    Options { format, ..options.clone() }
+}
+
+// Un canal alpha entièrement opaque ne compte pas : le JPEG reste alors candidat
+fn has_transparency(img: &DynamicImage) -> bool {
+    img.color().has_alpha() && img.to_rgba8().pixels().any(|p| p[3] < u8::MAX)
+}
@@ -0,0 +1,226 @@
+/*
+ * Copyright (C) 2026  Romain Lathuiliere
+ * * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ */
+
+use rayon::prelude::*;
+use serde::Serialize;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::control::BatchControl;
+use crate::engine::{compress_file_with_control, Options, Outcome};
+use crate::error::{Error, Result};
+use crate::format::OutputFormat;
+
+/// Résultat par image, tel qu'envoyé au frontend (`img-processed`) ou affiché par la CLI.
+#[derive(Debug, Serialize, Clone)]
+pub struct ProcessResult {
+    pub original: String,
+    pub status: String,
+    pub error_msg: Option<String>,
+    // Erreur structurée (code stable) pour que l'UI réagisse au type d'échec
+    pub error: Option<Error>,
+    pub new_path: Option<String>,
+    // Détails de la compression (absents en cas d'erreur ou d'annulation)
+    pub original_size: Option<u64>,
+    pub output_size: Option<u64>,
+    pub savings_percent: Option<f64>,
+    pub input_width: Option<u32>,
+    pub input_height: Option<u32>,
+    pub output_width: Option<u32>,
+    pub output_height: Option<u32>,
+    pub format: Option<OutputFormat>,
+    pub quality: Option<u8>,
+    // Format automatique : candidats comparés et raison du choix
+    pub format_reason: Option<String>,
+    // Perte signalée (ex : animation aplatie en image fixe)
+    pub warning: Option<String>,
+    pub encode_ms: Option<u64>,
+    // Statut "skipped-*" : taille de l'encodage écarté
+    pub rejected_size: Option<u64>,
+}
+
+impl ProcessResult {
+    pub fn new(original: &str, res: Result<Outcome>) -> Self {
+        match res {
+            Ok(outcome) => {
+                // Encodage écarté : la sortie éventuelle est une copie de la source
+                let kept = outcome.skipped.is_none();
+                let status = outcome.skipped.map_or("success", |reason| reason.status());
+                ProcessResult {
+                    new_path: outcome.output.map(|p| p.to_string_lossy().to_string()),
+                    original_size: Some(outcome.original_size),
+                    output_size: outcome.output_size,
+                    savings_percent: outcome.output_size.map(|size| savings_percent(outcome.original_size, size)),
+                    input_width: Some(outcome.input_width),
+                    input_height: Some(outcome.input_height),
+                    output_width: Some(outcome.output_width),
+                    output_height: Some(outcome.output_height),
+                    format: kept.then_some(outcome.format),
+                    quality: kept.then_some(outcome.quality),
+                    format_reason: outcome.format_reason.filter(|_| kept),
+                    warning: outcome.warning.filter(|_| kept),
+                    encode_ms: Some(outcome.encode_ms),
+                    rejected_size: outcome.skipped.map(|reason| reason.encoded_size()),
+                    ..ProcessResult::empty(original, status)
+                }
+            }
+            Err(e) => ProcessResult {
+                error_msg: Some(e.to_string()),
+                error: Some(e),
+                ..ProcessResult::empty(original, "error")
+            },
+        }
+    }
+
+    fn empty(original: &str, status: &str) -> Self {
+        ProcessResult {
+            original: original.to_string(),
+            status: status.to_string(),
+            error_msg: None,
+            error: None,
+            new_path: None,
+            original_size: None,
+            output_size: None,
+            savings_percent: None,
+            input_width: None,
+            input_height: None,
+            output_width: None,
+            output_height: None,
+            format: None,
+            quality: None,
+            format_reason: None,
+            warning: None,
+            encode_ms: None,
+            rejected_size: None,
+        }
+    }
+
+    // Image abandonnée en cours d'encodage suite à une annulation
+    pub fn cancelled(original: &str) -> Self {
+        ProcessResult::empty(original, "cancelled")
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.status == "success"
+    }
+}
+
+// Gain en % (négatif si la sortie est plus lourde), arrondi à 0.1
+fn savings_percent(original_size: u64, output_size: u64) -> f64 {
+    if original_size == 0 {
+        return 0.0;
+    }
+    let percent = (1.0 - output_size as f64 / original_size as f64) * 100.0;
+    (percent * 10.0).round() / 10.0
+}
+
+// Nombre de fichiers les plus lents remontés dans le résumé
+const SLOWEST_COUNT: usize = 5;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SlowFile {
+    pub path: String,
+    pub encode_ms: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FailedFile {
+    pub path: String,
+    pub code: &'static str,
+    pub error: String,
+}
+
+/// Résumé d'un batch terminé (payload de `batch-finished`, fin de sortie de la CLI).
+#[derive(Debug, Serialize, Clone)]
+pub struct BatchSummary {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    // Octets en entrée / en sortie des images effectivement traitées
+    pub input_bytes: u64,
+    pub output_bytes: u64,
+    pub elapsed_ms: u64,
+    pub slowest: Vec<SlowFile>,
+    pub errors: Vec<FailedFile>,
+}
+
+impl BatchSummary {
+    pub fn new(results: &[ProcessResult], elapsed: Duration) -> Self {
+        let mut slowest: Vec<SlowFile> = results
+            .iter()
+            .filter_map(|r| Some(SlowFile { path: r.original.clone(), encode_ms: r.encode_ms? }))
+            .collect();
+        slowest.sort_by(|a, b| b.encode_ms.cmp(&a.encode_ms));
+        slowest.truncate(SLOWEST_COUNT);
+
+        let errors = results
+            .iter()
+            .filter(|r| r.status == "error")
+            .map(|r| FailedFile {
+                path: r.original.clone(),
+                code: r.error.as_ref().map(Error::code).unwrap_or("unknown"),
+                error: r.error_msg.clone().unwrap_or_default(),
+            })
+            .collect();
+
+        BatchSummary {
+            total: results.len(),
+            succeeded: results.iter().filter(|r| r.is_success()).count(),
+            failed: results.iter().filter(|r| r.status == "error").count(),
+            skipped: results.iter().filter(|r| r.status.starts_with("skipped")).count(),
+            input_bytes: results.iter().filter_map(|r| r.original_size).sum(),
+            output_bytes: results.iter().filter_map(|r| r.output_size).sum(),
+            elapsed_ms: elapsed.as_millis() as u64,
+            slowest,
+            errors,
+        }
+    }
+}
+
+/// Progression d'un batch, relayée en événements Tauri ou en lignes de terminal.
+#[derive(Debug)]
+pub enum BatchEvent<'a> {
+    Started(&'a str),
+    Processed(&'a ProcessResult),
+}
+
+/// Compresse tous les chemins en parallèle dans le pool rayon courant.
+/// Le pool est choisi par l'appelant (`pool.install(...)`).
+///
+/// Une fois `control` annulé, plus aucune image n'est démarrée ; celles en cours
+/// sont abandonnées avant écriture et remontées avec le statut "cancelled".
+/// Une pause laisse chaque worker finir son image puis attendre `resume()`.
+/// Un panic pendant une image est converti en erreur `internal` pour ce seul fichier.
+pub fn run_batch<F>(paths: &[String], options: &Options, control: &BatchControl, on_event: F) -> Vec<ProcessResult>
+where
+    F: Fn(BatchEvent<'_>) + Sync,
+{
+    paths
+        .par_iter()
+        .filter_map(|path_str| {
+            control.wait_if_paused();
+            if control.is_cancelled() {
+                return None;
+            }
+            on_event(BatchEvent::Started(path_str));
+            // Un panic dans un codec ne doit emporter que ce fichier, pas le batch entier
+            let path = Path::new(path_str);
+            let compressed = catch_unwind(AssertUnwindSafe(|| compress_file_with_control(path, options, control)))
+                .unwrap_or_else(|payload| Err(Error::panic(payload.as_ref())));
+            let result = match compressed {
+                Ok(Some(outcome)) => ProcessResult::new(path_str, Ok(outcome)),
+                Ok(None) => ProcessResult::cancelled(path_str),
+                Err(e) => ProcessResult::new(path_str, Err(e)),
+            };
+            on_event(BatchEvent::Processed(&result));
+            Some(result)
+        })
+        .collect()
+}
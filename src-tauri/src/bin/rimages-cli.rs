@@ -0,0 +1,366 @@
+/*
+ * Copyright (C) 2026  Romain Lathuiliere
+ * * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ */
+
+// CLI sans fenêtre : même pipeline que la commande `compress_images` (CI, serveurs, scripts).
+
+use clap::Parser;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Instant;
+use tauri_app_lib::{
+    build_pool, run_batch, write_report, AvifAlphaMode, AvifBitDepth, AvifColorModel, AvifOptions, BatchControl,
+    BatchEvent, BatchSummary, IfLarger, JpegOptions, JpegSubsampling, JxlOptions, Options, OutputFormat, PngOptions,
+    ReportFormat, WebpOptions,
+};
+
+#[derive(Debug, Parser)]
+#[command(name = "rimages", version, about = "Compresse des images en JPG, PNG, WebP, AVIF ou JPEG XL (ou choix automatique)")]
+struct Cli {
+    /// Fichiers ou motifs glob (ex: "photos/*.jpg")
+    #[arg(required = true)]
+    inputs: Vec<String>,
+
+    /// Format de sortie : webp, avif, png, jpg, jxl, ou auto (le plus léger par image)
+    #[arg(short, long, default_value = "webp")]
+    format: OutputFormat,
+
+    /// Qualité (0-100)
+    #[arg(short, long, default_value_t = 85, value_parser = clap::value_parser!(u8).range(0..=100))]
+    quality: u8,
+
+    /// Sortie sans perte (webp, png, avif, jxl ; en jxl, un JPEG est transcodé réversiblement)
+    #[arg(long)]
+    lossless: bool,
+
+    /// Taille maximale par image (ex: 200KB, 1.5MB) : la qualité est cherchée automatiquement
+    #[arg(long, value_parser = parse_size)]
+    target_size: Option<u64>,
+
+    /// SSIM minimal (0-1, ex: 0.98) : la plus basse qualité qui le respecte est choisie
+    #[arg(long, conflicts_with = "target_size")]
+    min_ssim: Option<f64>,
+
+    /// Vitesse AVIF (1 = plus lent/plus petit, 10 = plus rapide)
+    #[arg(long, default_value_t = 4)]
+    avif_speed: u8,
+
+    /// Qualité du canal alpha AVIF (0-100)
+    #[arg(long)]
+    avif_alpha_quality: Option<u8>,
+
+    /// Profondeur AVIF : 8 ou 10 bits
+    #[arg(long)]
+    avif_bit_depth: Option<AvifBitDepth>,
+
+    /// Modèle de couleur AVIF : ycbcr ou rgb
+    #[arg(long, default_value = "ycbcr")]
+    avif_color_model: AvifColorModel,
+
+    /// Couleurs sous les pixels transparents : clean, dirty ou premultiplied
+    #[arg(long, default_value = "clean")]
+    avif_alpha_mode: AvifAlphaMode,
+
+    /// Effort WebP : 0 = rapide, 6 = plus lent et plus petit
+    #[arg(long, default_value_t = 4)]
+    webp_method: u8,
+
+    /// Qualité du canal alpha WebP (0-100)
+    #[arg(long, default_value_t = 100)]
+    webp_alpha_quality: u8,
+
+    /// Mode near-lossless WebP (0 = prétraitement max, 100 = aucun)
+    #[arg(long)]
+    webp_near_lossless: Option<u8>,
+
+    /// Garde les couleurs sous les pixels transparents (WebP)
+    #[arg(long)]
+    webp_exact: bool,
+
+    /// Conversion RGB -> YUV précise (évite les bavures sur le texte rouge)
+    #[arg(long)]
+    webp_sharp_yuv: bool,
+
+    /// Taille visée par libwebp (ex: 100KB), à combiner avec --webp-passes
+    #[arg(long, value_parser = parse_size)]
+    webp_target_size: Option<u64>,
+
+    /// PSNR visé par libwebp (dB)
+    #[arg(long)]
+    webp_target_psnr: Option<f32>,
+
+    /// Nombre de passes libwebp pour atteindre la cible (1-10)
+    #[arg(long, default_value_t = 1)]
+    webp_passes: u8,
+
+    /// JPEG baseline au lieu de progressif (désactive aussi le trellis)
+    #[arg(long)]
+    jpeg_baseline: bool,
+
+    /// Sous-échantillonnage JPEG : 444, 422 ou 420
+    #[arg(long, default_value = "420")]
+    jpeg_subsampling: JpegSubsampling,
+
+    /// Tables de Huffman standard au lieu de tables optimisées (avec --jpeg-baseline seulement)
+    #[arg(long, requires = "jpeg_baseline")]
+    jpeg_no_optimize_huffman: bool,
+
+    /// Désactive la quantification trellis (plus rapide, plus lourd)
+    #[arg(long)]
+    jpeg_no_trellis: bool,
+
+    /// Effort JPEG XL (1 = rapide, 9 = plus lent et plus petit)
+    #[arg(long, default_value_t = 7)]
+    jxl_effort: u8,
+
+    /// Qualité minimale acceptée pour la quantification PNG
+    #[arg(long)]
+    png_min_quality: Option<u8>,
+
+    /// Si la sortie est plus lourde que la source : keep, copy, skip ou retry
+    /// (une image redimensionnée est toujours écrite, retry compris)
+    #[arg(long, default_value = "keep")]
+    if_larger: IfLarger,
+
+    /// Gain minimal en % : en dessous, la source est gardée (statut skipped-threshold).
+    /// Sans effet sur une image redimensionnée, toujours écrite
+    #[arg(long)]
+    min_savings_percent: Option<f64>,
+
+    /// Gain minimal en taille (ex: 20KB) : en dessous, la source est gardée
+    #[arg(long, value_parser = parse_size)]
+    min_savings_size: Option<u64>,
+
+    /// Copier la source dans le dossier de sortie quand le gain est insuffisant
+    #[arg(long)]
+    copy_skipped: bool,
+
+    /// Largeur maximale en pixels
+    #[arg(long)]
+    max_width: Option<u32>,
+
+    /// Hauteur maximale en pixels
+    #[arg(long)]
+    max_height: Option<u32>,
+
+    /// Dossier de sortie
+    #[arg(short, long, default_value = ".")]
+    output_dir: PathBuf,
+
+    /// Nombre de workers (défaut : tous les cœurs)
+    #[arg(short = 'j', long)]
+    threads: Option<usize>,
+
+    /// Priorité basse et CPU plafonné à la moitié des cœurs
+    #[arg(long)]
+    background: bool,
+
+    /// Écrit un rapport (csv ou json) dans le dossier de sortie
+    #[arg(long)]
+    report: Option<ReportFormat>,
+}
+
+// "200KB", "1.5MB", "300000" -> octets (unités en base 1024)
+fn parse_size(value: &str) -> Result<u64, String> {
+    let upper = value.trim().to_ascii_uppercase();
+    let (number, multiplier) = if let Some(n) = upper.strip_suffix("MB").or_else(|| upper.strip_suffix('M')) {
+        (n, 1024.0 * 1024.0)
+    } else if let Some(n) = upper.strip_suffix("KB").or_else(|| upper.strip_suffix('K')) {
+        (n, 1024.0)
+    } else {
+        (upper.strip_suffix('B').unwrap_or(&upper), 1.0)
+    };
+    let number: f64 = number.trim().parse().map_err(|_| format!("taille invalide: {}", value))?;
+    if number <= 0.0 {
+        return Err(format!("taille invalide: {}", value));
+    }
+    Ok((number * multiplier) as u64)
+}
+
+// Les motifs glob sont développés ici (le shell ne le fait pas sous Windows)
+fn expand_inputs(inputs: &[String]) -> Vec<String> {
+    let mut paths = Vec::new();
+    for input in inputs {
+        let is_pattern = input.contains(['*', '?', '[']);
+        match glob::glob(input) {
+            Ok(entries) if is_pattern => {
+                paths.extend(entries.flatten().filter(|p| p.is_file()).map(|p| p.to_string_lossy().to_string()));
+            }
+            _ => paths.push(input.clone()),
+        }
+    }
+    paths
+}
+
+fn print_summary(summary: &BatchSummary) {
+    println!(
+        "\n{} compressed, {} failed, {} skipped ({} total) in {:.1}s",
+        summary.succeeded,
+        summary.failed,
+        summary.skipped,
+        summary.total,
+        summary.elapsed_ms as f64 / 1000.0
+    );
+    println!("{} bytes -> {} bytes", summary.input_bytes, summary.output_bytes);
+    if !summary.slowest.is_empty() {
+        println!("Slowest:");
+        for file in &summary.slowest {
+            println!("  {} ms  {}", file.encode_ms, file.path);
+        }
+    }
+    if !summary.errors.is_empty() {
+        println!("Errors:");
+        for file in &summary.errors {
+            println!("  {}: {}", file.path, file.error);
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let paths = expand_inputs(&cli.inputs);
+    if paths.is_empty() {
+        eprintln!("Aucun fichier ne correspond aux entrées");
+        return ExitCode::FAILURE;
+    }
+
+    if let Err(e) = std::fs::create_dir_all(&cli.output_dir) {
+        eprintln!("Impossible de créer {}: {}", cli.output_dir.display(), e);
+        return ExitCode::FAILURE;
+    }
+
+    let pool = match build_pool(cli.threads, cli.background) {
+        Ok(pool) => pool,
+        Err(e) => {
+            eprintln!("Impossible de créer le pool de workers: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let options = Options {
+        output_dir: cli.output_dir,
+        format: cli.format,
+        quality: cli.quality,
+        max_width: cli.max_width,
+        max_height: cli.max_height,
+        lossless: cli.lossless,
+        target_size: cli.target_size,
+        min_ssim: cli.min_ssim,
+        avif: AvifOptions {
+            speed: cli.avif_speed,
+            alpha_quality: cli.avif_alpha_quality,
+            bit_depth: cli.avif_bit_depth,
+            color_model: cli.avif_color_model,
+            alpha_mode: cli.avif_alpha_mode,
+        },
+        png: PngOptions { min_quality: cli.png_min_quality },
+        jxl: JxlOptions { effort: cli.jxl_effort },
+        jpeg: JpegOptions {
+            progressive: !cli.jpeg_baseline,
+            subsampling: cli.jpeg_subsampling,
+            optimize_huffman: !cli.jpeg_no_optimize_huffman,
+            trellis: !cli.jpeg_baseline && !cli.jpeg_no_trellis,
+        },
+        webp: WebpOptions {
+            method: cli.webp_method,
+            alpha_quality: cli.webp_alpha_quality,
+            near_lossless: cli.webp_near_lossless,
+            exact: cli.webp_exact,
+            sharp_yuv: cli.webp_sharp_yuv,
+            target_size: cli.webp_target_size,
+            target_psnr: cli.webp_target_psnr,
+            passes: cli.webp_passes,
+        },
+        if_larger: cli.if_larger,
+        min_savings_percent: cli.min_savings_percent,
+        min_savings_bytes: cli.min_savings_size,
+        copy_skipped: cli.copy_skipped,
+    };
+    if let Err(e) = options.validate() {
+        eprintln!("{}", e);
+        return ExitCode::FAILURE;
+    }
+
+    let started = Instant::now();
+    let results = pool.install(|| {
+        run_batch(&paths, &options, &BatchControl::new(), |event| {
+            if let BatchEvent::Processed(result) = event {
+                match (&result.new_path, &result.error_msg) {
+                    _ if result.status.starts_with("skipped") => println!(
+                        "{} {} (encoded {} bytes){}",
+                        result.status,
+                        result.original,
+                        result.rejected_size.unwrap_or_default(),
+                        result.new_path.as_deref().map(|p| format!(", copied to {}", p)).unwrap_or_default()
+                    ),
+                    (Some(new_path), _) => println!(
+                        "ok     {} -> {} ({} -> {} bytes, {}%, {} ms)",
+                        result.original,
+                        new_path,
+                        result.original_size.unwrap_or_default(),
+                        result.output_size.unwrap_or_default(),
+                        result.savings_percent.unwrap_or_default(),
+                        result.encode_ms.unwrap_or_default()
+                    ),
+                    (None, msg) => println!("error  {}: {}", result.original, msg.as_deref().unwrap_or("?")),
+                }
+                if let Some(reason) = &result.format_reason {
+                    println!("       {}", reason);
+                }
+                if let Some(warning) = &result.warning {
+                    println!("       warning: {}", warning);
+                }
+            }
+        })
+    });
+
+    let summary = BatchSummary::new(&results, started.elapsed());
+    print_summary(&summary);
+
+    if let Some(format) = cli.report {
+        match write_report(&results, &options.output_dir, format) {
+            Ok(path) => println!("Report: {}", path.display()),
+            Err(e) => {
+                eprintln!("Impossible d'écrire le rapport: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    if summary.failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_size;
+
+    #[test]
+    fn plain_numbers_are_bytes() {
+        assert_eq!(parse_size("300000"), Ok(300_000));
+        assert_eq!(parse_size("512B"), Ok(512));
+    }
+
+    #[test]
+    fn units_are_base_1024_and_case_insensitive() {
+        assert_eq!(parse_size("200KB"), Ok(200 * 1024));
+        assert_eq!(parse_size("20k"), Ok(20 * 1024));
+        assert_eq!(parse_size("1.5MB"), Ok(1024 * 1024 * 3 / 2));
+        assert_eq!(parse_size(" 2 mb "), Ok(2 * 1024 * 1024));
+    }
+
+    #[test]
+    fn empty_zero_negative_or_garbage_is_rejected() {
+        for value in ["", "0", "0KB", "-5", "abc", "KB"] {
+            assert!(parse_size(value).is_err(), "{} should be rejected", value);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+/*
+ * Copyright (C) 2026  Romain Lathuiliere
+ * * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ */
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+
+/// Signaux partagés entre un batch en cours et ceux qui le pilotent (commandes Tauri, Ctrl-C...).
+#[derive(Debug, Default)]
+pub struct BatchControl {
+    cancelled: AtomicBool,
+    paused: Mutex<bool>,
+    resumed: Condvar,
+}
+
+impl BatchControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        // Le verrou évite de perdre le réveil d'un worker qui s'apprête à attendre
+        let _paused = self.paused.lock().unwrap();
+        self.cancelled.store(true, Ordering::SeqCst);
+        // Réveille les workers en pause pour qu'ils constatent l'annulation
+        self.resumed.notify_all();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub fn pause(&self) {
+        *self.paused.lock().unwrap() = true;
+    }
+
+    pub fn resume(&self) {
+        *self.paused.lock().unwrap() = false;
+        self.resumed.notify_all();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.paused.lock().unwrap()
+    }
+
+    /// Bloque le worker appelant tant que le batch est en pause (sauf annulation).
+    /// Appelé entre deux images : l'image en cours se termine toujours.
+    pub fn wait_if_paused(&self) {
+        let mut paused = self.paused.lock().unwrap();
+        while *paused && !self.is_cancelled() {
+            paused = self.resumed.wait(paused).unwrap();
+        }
+    }
+
+    // À appeler avant de réutiliser le même contrôle pour un nouveau batch
+    pub fn reset(&self) {
+        self.cancelled.store(false, Ordering::SeqCst);
+        *self.paused.lock().unwrap() = false;
+    }
+}
@@ -0,0 +1,342 @@
+/*
+ * Copyright (C) 2026  Romain Lathuiliere
+ * * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ */
+
+// Ouverture des images sources. Tout passe par `image`, sauf le HEIC/HEIF (libheif,
+// fonctionnalité "heif") et les RAW d'appareil photo (aperçu JPEG embarqué, sinon
+// dématriçage imagepipe).
+
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageFormat, ImageReader, RgbImage};
+#[cfg(feature = "heif")]
+use image::RgbaImage;
+#[cfg(feature = "heif")]
+use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+use std::fs::{self, File};
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+use crate::error::{Error, Result};
+
+// Marques "ftyp" des images HEVC. Les marques génériques mif1/msf1 sont aussi celles de
+// l'AVIF (lu par `image`) : seules les marques HEVC, majeure ou compatible, désignent du HEIC
+const HEIC_BRANDS: [&[u8; 4]; 6] = [b"heic", b"heix", b"heim", b"heis", b"hevc", b"hevx"];
+// Taille maximale de boîte ftyp lue (quelques dizaines de marques compatibles)
+const FTYP_MAX_SIZE: usize = 256;
+
+const RAW_EXTENSIONS: [&str; 10] = ["dng", "cr2", "cr3", "nef", "nrw", "arw", "raf", "orf", "rw2", "pef"];
+// En dessous, l'aperçu embarqué n'est qu'une vignette : on dématrice le capteur
+const FULL_PREVIEW_MIN_EDGE: u32 = 2560;
+// Début de RAW lu pour les dimensions : en-têtes TIFF / ISO BMFF et, le plus souvent, l'aperçu
+const RAW_HEADER_SIZE: u64 = 8 << 20;
+
+/// AVIF animé (marque "avis", majeure ou compatible) : `image` n'en décode que la première image.
+pub(crate) fn is_avif_sequence(path: &Path) -> bool {
+    ftyp_brands(path).is_some_and(|brands| brands.contains(b"avis"))
+}
+
+/// Décode une image source, quel que soit son format.
+pub fn open_image(path: &Path) -> Result<DynamicImage> {
+    if is_heif(path) {
+        decode_heif(path)
+    } else if is_raw(path) {
+        decode_raw(path)
+    } else {
+        image::open(path).map_err(Error::decode)
+    }
+}
+
+/// Dimensions d'une image source, en ne lisant que l'en-tête quand c'est possible.
+pub fn read_dimensions(path: &Path) -> Result<(u32, u32)> {
+    if is_heif(path) {
+        heif_dimensions(path)
+    } else if is_raw(path) {
+        raw_header_dimensions(path)
+    } else {
+        image::image_dimensions(path).map_err(Error::decode)
+    }
+}
+
+// Détection par l'extension ou par les marques (majeure et compatibles) de la boîte ftyp
+fn is_heif(path: &Path) -> bool {
+    let by_extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .is_some_and(|ext| ext == "heic" || ext == "heif");
+    if by_extension {
+        return true;
+    }
+
+    ftyp_brands(path).is_some_and(|brands| brands.iter().any(|brand| HEIC_BRANDS.contains(&brand)))
+}
+
+// Marques d'un conteneur ISO BMFF (boîte ftyp en tête de fichier) : la majeure d'abord,
+// puis les compatibles (la version mineure qui les sépare est sautée)
+fn ftyp_brands(path: &Path) -> Option<Vec<[u8; 4]>> {
+    let mut header = Vec::with_capacity(FTYP_MAX_SIZE);
+    File::open(path).ok()?.take(FTYP_MAX_SIZE as u64).read_to_end(&mut header).ok()?;
+    if header.len() < 12 || &header[4..8] != b"ftyp" {
+        return None;
+    }
+    let size = (u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize).clamp(12, header.len());
+    let brands = header[8..12].chunks_exact(4).chain(header[16.min(size)..size].chunks_exact(4));
+    Some(brands.map(|brand| [brand[0], brand[1], brand[2], brand[3]]).collect())
+}
+
+#[cfg(feature = "heif")]
+fn heif_error(err: libheif_rs::HeifError) -> Error {
+    Error::Decode { details: err.to_string() }
+}
+
+// libheif n'accepte que des chemins UTF-8
+#[cfg(feature = "heif")]
+fn heif_path(path: &Path) -> Result<&str> {
+    path.to_str().ok_or_else(|| Error::Decode { details: format!("invalid path: {}", path.display()) })
+}
+
+// Image principale uniquement : les autres images du conteneur (rafales, miniatures)
+// et les images auxiliaires (profondeur, alpha séparé) sont ignorées.
+// libheif applique les transformations irot/imir : l'orientation est respectée.
+#[cfg(feature = "heif")]
+fn decode_heif(path: &Path) -> Result<DynamicImage> {
+    let ctx = HeifContext::read_from_file(heif_path(path)?).map_err(heif_error)?;
+    let handle = ctx.primary_image_handle().map_err(heif_error)?;
+    let has_alpha = handle.has_alpha_channel();
+    let chroma = if has_alpha { RgbChroma::Rgba } else { RgbChroma::Rgb };
+
+    let lib_heif = LibHeif::new();
+    let image = lib_heif.decode(&handle, ColorSpace::Rgb(chroma), None).map_err(heif_error)?;
+    let planes = image.planes();
+    let plane = planes.interleaved.ok_or_else(|| Error::Decode { details: "HEIF image has no RGB plane".to_string() })?;
+
+    // Les lignes peuvent être complétées (stride) : on ne garde que les pixels utiles
+    let channels = if has_alpha { 4 } else { 3 };
+    let row_len = plane.width as usize * channels;
+    let mut pixels = Vec::with_capacity(row_len * plane.height as usize);
+    for row in plane.data.chunks(plane.stride).take(plane.height as usize) {
+        pixels.extend_from_slice(&row[..row_len]);
+    }
+
+    let decoded = if has_alpha {
+        RgbaImage::from_raw(plane.width, plane.height, pixels).map(DynamicImage::ImageRgba8)
+    } else {
+        RgbImage::from_raw(plane.width, plane.height, pixels).map(DynamicImage::ImageRgb8)
+    };
+    decoded.ok_or_else(|| Error::Decode { details: "unexpected HEIF plane size".to_string() })
+}
+
+#[cfg(feature = "heif")]
+fn heif_dimensions(path: &Path) -> Result<(u32, u32)> {
+    let ctx = HeifContext::read_from_file(heif_path(path)?).map_err(heif_error)?;
+    let handle = ctx.primary_image_handle().map_err(heif_error)?;
+    Ok((handle.width(), handle.height()))
+}
+
+#[cfg(not(feature = "heif"))]
+fn decode_heif(_path: &Path) -> Result<DynamicImage> {
+    Err(Error::not_compiled("HEIF", "heif"))
+}
+
+#[cfg(not(feature = "heif"))]
+fn heif_dimensions(_path: &Path) -> Result<(u32, u32)> {
+    Err(Error::not_compiled("HEIF", "heif"))
+}
+
+fn is_raw(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .is_some_and(|ext| RAW_EXTENSIONS.contains(&ext.as_str()))
+}
+
+// JPEG embarqué dans un RAW, repéré par son marqueur SOI
+struct Preview {
+    offset: usize,
+    width: u32,
+    height: u32,
+    orientation: Option<Orientation>,
+}
+
+impl Preview {
+    fn oriented_dimensions(&self) -> (u32, u32) {
+        oriented_dimensions(self.width, self.height, self.orientation)
+    }
+}
+
+fn oriented_dimensions(width: u32, height: u32, orientation: Option<Orientation>) -> (u32, u32) {
+    match orientation {
+        Some(
+            Orientation::Rotate90 | Orientation::Rotate270 | Orientation::Rotate90FlipH | Orientation::Rotate270FlipH,
+        ) => (height, width),
+        _ => (width, height),
+    }
+}
+
+// Aperçu pleine taille rendu par le boîtier (couleurs et balance des blancs de l'appareil) :
+// bien plus rapide qu'un dématriçage, on le préfère quand il existe
+fn decode_raw(path: &Path) -> Result<DynamicImage> {
+    let data = fs::read(path)?;
+    if let Some(preview) = full_preview(&data) {
+        // Aperçu illisible malgré un en-tête valide : on se rabat sur le capteur
+        if let Ok(mut img) = image::load_from_memory_with_format(&data[preview.offset..], ImageFormat::Jpeg) {
+            if let Some(orientation) = preview.orientation {
+                img.apply_orientation(orientation);
+            }
+            return Ok(img);
+        }
+    }
+    demosaic(path)
+}
+
+// Dématriçage et balance des blancs "as shot" par imagepipe (rawloader), orientation comprise
+fn demosaic(path: &Path) -> Result<DynamicImage> {
+    let mut pipeline = imagepipe::Pipeline::new_from_file(path).map_err(|details| Error::Decode { details })?;
+    let output = pipeline.output_8bit(None).map_err(|details| Error::Decode { details })?;
+    RgbImage::from_raw(output.width as u32, output.height as u32, output.data)
+        .map(DynamicImage::ImageRgb8)
+        .ok_or_else(|| Error::Decode { details: "unexpected RAW output size".to_string() })
+}
+
+// Dimensions sans lire tout le RAW : aperçu pleine taille du début de fichier, sinon taille
+// déclarée dans l'EXIF, et en dernier recours l'en-tête du capteur
+fn raw_header_dimensions(path: &Path) -> Result<(u32, u32)> {
+    let mut header = Vec::new();
+    File::open(path)?.take(RAW_HEADER_SIZE).read_to_end(&mut header)?;
+    if let Some(preview) = full_preview(&header) {
+        return Ok(preview.oriented_dimensions());
+    }
+    match raw_exif(&header).and_then(|exif| exif_dimensions(&exif)) {
+        Some(dimensions) => Ok(dimensions),
+        None => raw_dimensions(path),
+    }
+}
+
+// Dimensions de la sortie d'imagepipe (recadrage et orientation compris), sans dématriçage
+fn raw_dimensions(path: &Path) -> Result<(u32, u32)> {
+    use rawloader::Orientation;
+
+    let raw = rawloader::decode_file(path).map_err(|e| Error::Decode { details: e.to_string() })?;
+    // Recadrage dans l'ordre haut, droite, bas, gauche
+    let width = raw.width.saturating_sub(raw.crops[1] + raw.crops[3]) as u32;
+    let height = raw.height.saturating_sub(raw.crops[0] + raw.crops[2]) as u32;
+    Ok(match raw.orientation {
+        Orientation::Transpose | Orientation::Rotate90 | Orientation::Transverse | Orientation::Rotate270 => {
+            (height, width)
+        }
+        _ => (width, height),
+    })
+}
+
+// Plus grand JPEG embarqué, s'il est assez grand pour servir d'image source
+fn full_preview(data: &[u8]) -> Option<Preview> {
+    let mut best: Option<Preview> = None;
+    let mut offset = 0;
+    while let Some(pos) = data[offset..].windows(3).position(|w| w == [0xFF, 0xD8, 0xFF]) {
+        let start = offset + pos;
+        let stream = &data[start..];
+        // Lecture de l'en-tête seulement : les faux positifs échouent tout de suite
+        let dimensions = if is_lossless_jpeg(stream) {
+            None
+        } else {
+            ImageReader::with_format(Cursor::new(stream), ImageFormat::Jpeg).into_dimensions().ok()
+        };
+        if let Some((width, height)) = dimensions {
+            let area = width as u64 * height as u64;
+            if best.as_ref().is_none_or(|b| area > b.width as u64 * b.height as u64) {
+                best = Some(Preview { offset: start, width, height, orientation: None });
+            }
+        }
+        offset = start + 3;
+    }
+
+    let mut preview = best.filter(|p| p.width.max(p.height) >= FULL_PREVIEW_MIN_EDGE)?;
+    preview.orientation = raw_exif(data).and_then(|exif| raw_orientation(&exif));
+    Some(preview)
+}
+
+// Premier marqueur SOF du flux : SOF3/7/11/15 = JPEG sans perte, utilisé pour les données
+// capteur des CR2 / DNG et que le décodeur `image` ne lit pas
+fn is_lossless_jpeg(jpeg: &[u8]) -> bool {
+    let mut pos = 2;
+    while pos + 4 <= jpeg.len() && jpeg[pos] == 0xFF {
+        let marker = jpeg[pos + 1];
+        if (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            return matches!(marker, 0xC3 | 0xC7 | 0xCB | 0xCF);
+        }
+        pos += 2 + u16::from_be_bytes([jpeg[pos + 2], jpeg[pos + 3]]) as usize;
+    }
+    false
+}
+
+// IFD0 du RAW : en tête de fichier pour les formats TIFF (DNG, CR2, NEF, ARW...), dans la
+// boîte CMT1 pour le CR3 (conteneur ISO BMFF)
+fn raw_exif(data: &[u8]) -> Option<exif::Exif> {
+    let tiff = cr3_cmt1(data).unwrap_or(data);
+    exif::Reader::new().read_raw(tiff.to_vec()).ok()
+}
+
+// CR3 (marque "crx ") : la boîte CMT1 de l'uuid Canon contient un TIFF complet avec l'IFD0
+fn cr3_cmt1(data: &[u8]) -> Option<&[u8]> {
+    if data.get(4..12)? != b"ftypcrx " {
+        return None;
+    }
+    let name = data.windows(4).position(|w| w == b"CMT1")?;
+    let start = name.checked_sub(4)?;
+    let size = u32::from_be_bytes(data[start..name].try_into().ok()?) as usize;
+    data.get(name + 4..start + size)
+}
+
+// Taille de l'image développée déclarée par le boîtier, orientation comprise
+fn exif_dimensions(exif: &exif::Exif) -> Option<(u32, u32)> {
+    let dimension = |tag| exif.get_field(tag, exif::In::PRIMARY)?.value.get_uint(0);
+    let width = dimension(exif::Tag::PixelXDimension)?;
+    let height = dimension(exif::Tag::PixelYDimension)?;
+    Some(oriented_dimensions(width, height, raw_orientation(exif)))
+}
+
+// Les aperçus sont stockés dans le sens du capteur : l'orientation est celle de l'IFD0 du RAW
+fn raw_orientation(exif: &exif::Exif) -> Option<Orientation> {
+    let field = exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)?;
+    let value = field.value.get_uint(0)?;
+    Orientation::from_exif(u8::try_from(value).ok()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ftyp "crx " suivi d'une boîte CMT1 de `payload`
+    fn cr3_with_cmt1(payload: &[u8]) -> Vec<u8> {
+        let mut data = vec![0, 0, 0, 16];
+        data.extend_from_slice(b"ftypcrx ");
+        data.extend_from_slice(&[0, 0, 0, 1]);
+        data.extend_from_slice(&(payload.len() as u32 + 8).to_be_bytes());
+        data.extend_from_slice(b"CMT1");
+        data.extend_from_slice(payload);
+        data.extend_from_slice(b"trailing");
+        data
+    }
+
+    #[test]
+    fn cr3_cmt1_extracts_the_tiff_payload() {
+        let data = cr3_with_cmt1(b"II*\0tiff");
+        assert_eq!(cr3_cmt1(&data), Some(&b"II*\0tiff"[..]));
+    }
+
+    #[test]
+    fn cr3_cmt1_ignores_other_containers() {
+        let mut data = cr3_with_cmt1(b"II*\0tiff");
+        data[8..12].copy_from_slice(b"heic");
+        assert_eq!(cr3_cmt1(&data), None);
+    }
+
+    #[test]
+    fn oriented_dimensions_swap_for_quarter_turns() {
+        assert_eq!(oriented_dimensions(6000, 4000, Some(Orientation::Rotate90)), (4000, 6000));
+        assert_eq!(oriented_dimensions(6000, 4000, Some(Orientation::FlipHorizontal)), (6000, 4000));
+        assert_eq!(oriented_dimensions(6000, 4000, None), (6000, 4000));
+    }
+}
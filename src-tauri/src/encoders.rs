@@ -0,0 +1,378 @@
+/*
+ * Copyright (C) 2026  Romain Lathuiliere
+ * * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ */
+
+use image::{ColorType, DynamicImage, RgbaImage};
+use rgb::FromSlice;
+use std::borrow::Cow;
+use std::io::Cursor;
+use std::panic::AssertUnwindSafe;
+
+use crate::error::{Error, Result};
+use crate::format::{
+    AvifAlphaMode, AvifBitDepth, AvifColorModel, AvifOptions, JpegOptions, JpegSubsampling, JxlOptions, PngOptions,
+    WebpOptions,
+};
+
+// --- ENCODEURS SPÉCIAUX ---
+
+// libwebp ne lit que du RGB(A) 8 bits : gris, 16 bits et flottants sont convertis
+fn webp_input(img: &DynamicImage) -> Cow<'_, DynamicImage> {
+    match img {
+        DynamicImage::ImageRgb8(_) | DynamicImage::ImageRgba8(_) => Cow::Borrowed(img),
+        _ if img.color().has_alpha() => Cow::Owned(DynamicImage::ImageRgba8(img.to_rgba8())),
+        _ => Cow::Owned(DynamicImage::ImageRgb8(img.to_rgb8())),
+    }
+}
+
+// Plus de 8 bits par canal (16 bits ou flottant)
+fn is_high_depth(img: &DynamicImage) -> bool {
+    let color = img.color();
+    color.bytes_per_pixel() > color.channel_count()
+}
+
+// Un encodage sans perte en 8 bits tronquerait les valeurs : on refuse plutôt que de mentir
+fn reject_high_depth(codec: &str, img: &DynamicImage) -> Result<()> {
+    if is_high_depth(img) {
+        return Err(Error::UnsupportedFormat {
+            details: format!("{} lossless is limited to 8 bits per channel (input is {:?})", codec, img.color()),
+        });
+    }
+    Ok(())
+}
+
+pub fn encode_webp(img: &DynamicImage, quality: u8, webp_options: &WebpOptions) -> Result<Vec<u8>> {
+    let img = webp_input(img);
+    let encoder = match webp::Encoder::from_image(&img) {
+        Ok(enc) => enc,
+        Err(e) => return Err(Error::encode("webp", e)),
+    };
+    let config = webp_config(quality, webp_options)?;
+    let memory = encoder.encode_advanced(&config).map_err(|e| Error::encode("webp", format!("{:?}", e)))?;
+    Ok(memory.to_vec())
+}
+
+fn webp_config(quality: u8, webp_options: &WebpOptions) -> Result<webp::WebPConfig> {
+    let mut config = webp::WebPConfig::new().map_err(|_| Error::encode("webp", "invalid libwebp config"))?;
+    config.quality = quality as f32;
+    config.method = webp_options.method as i32;
+    config.alpha_quality = webp_options.alpha_quality as i32;
+    config.exact = webp_options.exact as i32;
+    config.use_sharp_yuv = webp_options.sharp_yuv as i32;
+    if let Some(near_lossless) = webp_options.near_lossless {
+        config.lossless = 1;
+        config.near_lossless = near_lossless as i32;
+    }
+    if let Some(target_size) = webp_options.target_size {
+        config.target_size = target_size.min(i32::MAX as u64) as i32;
+    }
+    if let Some(target_psnr) = webp_options.target_psnr {
+        config.target_PSNR = target_psnr;
+    }
+    config.pass = webp_options.passes as i32;
+    Ok(config)
+}
+
+/// WebP animé : chaque image (même taille) est affichée `delays_ms[i]` millisecondes.
+/// `loop_count` = 0 pour boucler indéfiniment.
+pub fn encode_webp_animation(
+    frames: &[RgbaImage],
+    delays_ms: &[u32],
+    loop_count: u16,
+    quality: u8,
+    lossless: bool,
+    webp_options: &WebpOptions,
+) -> Result<Vec<u8>> {
+    let (width, height) = frames.first().map(|f| f.dimensions()).ok_or_else(|| Error::encode("webp", "no frames"))?;
+    let mut config = webp_config(quality, webp_options)?;
+    if lossless {
+        force_lossless(&mut config);
+    }
+
+    let mut encoder = webp::AnimEncoder::new(width, height, &config);
+    encoder.set_loop_count(loop_count as i32);
+    // Horodatage de début de chaque image, en millisecondes depuis le début de l'animation
+    let mut timestamp: i32 = 0;
+    for (frame, delay) in frames.iter().zip(delays_ms) {
+        encoder.add_frame(webp::AnimFrame::from_rgba(frame.as_raw(), width, height, timestamp));
+        timestamp = timestamp.saturating_add(*delay as i32);
+    }
+    let memory = encoder.try_encode().map_err(|e| Error::encode("webp", format!("{:?}", e)))?;
+    Ok(memory.to_vec())
+}
+
+// Effort de compression VP8L (le `quality` d'un encodage sans perte), valeur de WebPEncodeLossless*
+const WEBP_LOSSLESS_EFFORT: u8 = 70;
+
+// Sans perte : VP8L, chaque pixel (alpha compris) est conservé. `exact` est forcé, sinon libwebp
+// réécrit le RGB sous les pixels transparents ; near-lossless et les cibles n'ont pas de sens ici
+fn force_lossless(config: &mut webp::WebPConfig) {
+    config.lossless = 1;
+    config.exact = 1;
+    config.near_lossless = 100;
+    config.target_size = 0;
+    config.target_PSNR = 0.0;
+}
+
+pub fn encode_webp_lossless(img: &DynamicImage, webp_options: &WebpOptions) -> Result<Vec<u8>> {
+    reject_high_depth("webp", img)?;
+    let img = webp_input(img);
+    let encoder = webp::Encoder::from_image(&img).map_err(|e| Error::encode("webp", e))?;
+    let mut config = webp_config(WEBP_LOSSLESS_EFFORT, webp_options)?;
+    force_lossless(&mut config);
+    let memory = encoder.encode_advanced(&config).map_err(|e| Error::encode("webp", format!("{:?}", e)))?;
+    Ok(memory.to_vec())
+}
+
+pub fn encode_avif(img: &DynamicImage, quality: u8, avif: &AvifOptions) -> Result<Vec<u8>> {
+    let rgba_img = img.to_rgba8();
+    let width = rgba_img.width();
+    let height = rgba_img.height();
+    let raw_pixels = rgba_img.as_raw();
+
+    let src_img = imgref::Img::new(raw_pixels.as_rgba(), width as usize, height as usize);
+    let mut encoder = ravif::Encoder::new()
+        .with_quality(quality as f32)
+        .with_speed(avif.speed)
+        .with_internal_color_model(match avif.color_model {
+            AvifColorModel::YCbCr => ravif::ColorModel::YCbCr,
+            AvifColorModel::Rgb => ravif::ColorModel::RGB,
+        })
+        .with_alpha_color_mode(match avif.alpha_mode {
+            AvifAlphaMode::Clean => ravif::AlphaColorMode::UnassociatedClean,
+            AvifAlphaMode::Dirty => ravif::AlphaColorMode::UnassociatedDirty,
+            AvifAlphaMode::Premultiplied => ravif::AlphaColorMode::Premultiplied,
+        });
+    if let Some(alpha_quality) = avif.alpha_quality {
+        encoder = encoder.with_alpha_quality(alpha_quality as f32);
+    }
+    if let Some(bit_depth) = avif.bit_depth {
+        encoder = encoder.with_bit_depth(match bit_depth {
+            AvifBitDepth::Eight => ravif::BitDepth::Eight,
+            AvifBitDepth::Ten => ravif::BitDepth::Ten,
+        });
+    }
+    let enc = encoder.encode_rgba(src_img);
+
+    match enc {
+        Ok(encoded) => Ok(encoded.avif_file),
+        Err(e) => Err(Error::encode("avif", e)),
+    }
+}
+
+// AVIF sans perte : quantizer 0 (qualité 100), modèle RGB sans conversion YCbCr,
+// 8 bits et alpha "dirty" (RGB des pixels transparents intact) pour relire exactement les mêmes valeurs
+pub fn encode_avif_lossless(img: &DynamicImage, avif: &AvifOptions) -> Result<Vec<u8>> {
+    reject_high_depth("avif", img)?;
+    let rgba_img = img.to_rgba8();
+    let src_img = imgref::Img::new(rgba_img.as_raw().as_rgba(), rgba_img.width() as usize, rgba_img.height() as usize);
+
+    ravif::Encoder::new()
+        .with_quality(100.0)
+        .with_alpha_quality(100.0)
+        .with_internal_color_model(ravif::ColorModel::RGB)
+        .with_alpha_color_mode(ravif::AlphaColorMode::UnassociatedDirty)
+        .with_bit_depth(ravif::BitDepth::Eight)
+        .with_speed(avif.speed)
+        .encode_rgba(src_img)
+        .map(|encoded| encoded.avif_file)
+        .map_err(|e| Error::encode("avif", e))
+}
+
+// LA MAGIE PNG (Quantification)
+pub fn encode_png(img: &DynamicImage, quality: u8, png_options: &PngOptions) -> Result<Vec<u8>> {
+    let rgba = img.to_rgba8();
+    let width = rgba.width();
+    let height = rgba.height();
+    let raw_pixels = rgba.as_raw();
+
+    // 1. Configurer imagequant (Liq)
+    let mut attr = imagequant::Attributes::new();
+    // Le slider qualité (0-100) contrôle l'agressivité de la réduction de couleurs
+    let min_q = png_options.min_quality.unwrap_or(quality.saturating_sub(20)); // Plage dynamique
+    attr.set_quality(min_q, quality).map_err(|e| Error::encode("png", format!("quality config: {:?}", e)))?;
+
+    // 2. Créer l'image pour Liq
+    // Note: imagequant demande des références, on utilise as_rgba()
+    let mut img_liq = attr.new_image(raw_pixels.as_rgba(), width as usize, height as usize, 0.0)
+        .map_err(|e| Error::encode("png", format!("image setup: {:?}", e)))?;
+
+    // 3. Quantifier (Calculer la palette)
+    let mut res = attr.quantize(&mut img_liq)
+        .map_err(|e| Error::encode("png", format!("quantize: {:?}", e)))?;
+
+    // 4. Appliquer la palette (Remapping)
+    let (palette, pixels) = res.remapped(&mut img_liq)
+        .map_err(|e| Error::encode("png", format!("remap: {:?}", e)))?;
+
+    // 5. Écrire le PNG final (Format Indexé)
+    let mut buffer = Vec::new();
+    let mut encoder = png::Encoder::new(&mut buffer, width, height);
+
+    encoder.set_color(png::ColorType::Indexed);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    // Conversion de la palette imagequant -> format attendu par png crate
+    let palette_vec: Vec<u8> = palette.iter().flat_map(|c| [c.r, c.g, c.b]).collect();
+    encoder.set_palette(palette_vec);
+    // Transparence de la palette (chunk tRNS), sinon les logos perdent leur fond transparent
+    if palette.iter().any(|c| c.a < u8::MAX) {
+        let alpha_vec: Vec<u8> = palette.iter().map(|c| c.a).collect();
+        encoder.set_trns(alpha_vec);
+    }
+
+    let mut writer = encoder.write_header().map_err(|e| Error::encode("png", e))?;
+    writer.write_image_data(&pixels).map_err(|e| Error::encode("png", e))?;
+    writer.finish().map_err(|e| Error::encode("png", e))?;
+
+    Ok(buffer)
+}
+
+// PNG sans perte : pas de quantification, juste l'optimisation filtres/deflate d'oxipng
+// (les réductions de profondeur ou de palette faites par oxipng sont elles aussi sans perte)
+pub fn encode_png_lossless(img: &DynamicImage) -> Result<Vec<u8>> {
+    let mut buf = Cursor::new(Vec::new());
+    img.write_to(&mut buf, image::ImageFormat::Png).map_err(|e| Error::encode("png", e))?;
+
+    let options = oxipng::Options::from_preset(2);
+    oxipng::optimize_from_memory(buf.get_ref(), &options).map_err(|e| Error::encode("png", e))
+}
+
+// mozjpeg signale ses erreurs internes par un panic : on le rattrape pour en faire une Error
+pub fn encode_jpeg(img: &DynamicImage, quality: u8, jpeg_options: &JpegOptions) -> Result<Vec<u8>> {
+    // Le JPEG n'accepte que du 8 bits sans alpha : on aplatit le reste en RGB (ou en gris)
+    let grayscale = matches!(img.color(), ColorType::L8 | ColorType::La8 | ColorType::L16 | ColorType::La16);
+    let (color_space, pixels) = if grayscale {
+        (mozjpeg::ColorSpace::JCS_GRAYSCALE, img.to_luma8().into_raw())
+    } else {
+        (mozjpeg::ColorSpace::JCS_RGB, img.to_rgb8().into_raw())
+    };
+    let (width, height) = (img.width() as usize, img.height() as usize);
+
+    let encoded = std::panic::catch_unwind(AssertUnwindSafe(|| -> std::io::Result<Vec<u8>> {
+        let mut comp = mozjpeg::Compress::new(color_space);
+        // Sans trellis ou en baseline : profil libjpeg-turbo, puis on réactive ce qui est demandé
+        if !jpeg_options.trellis || !jpeg_options.progressive {
+            comp.set_fastest_defaults();
+        }
+        if jpeg_options.progressive {
+            comp.set_progressive_mode();
+        }
+        comp.set_optimize_coding(jpeg_options.optimize_huffman);
+        comp.set_size(width, height);
+        comp.set_quality(quality as f32);
+        let chroma = match jpeg_options.subsampling {
+            JpegSubsampling::S444 => (1, 1),
+            JpegSubsampling::S422 => (2, 1),
+            JpegSubsampling::S420 => (2, 2),
+        };
+        if !grayscale {
+            comp.set_chroma_sampling_pixel_sizes((1, 1), chroma);
+        }
+
+        let mut started = comp.start_compress(Vec::with_capacity(50_000))?;
+        started.write_scanlines(&pixels)?;
+        started.finish()
+    }));
+
+    match encoded {
+        Ok(Ok(data)) => Ok(data),
+        Ok(Err(e)) => Err(Error::encode("jpeg", e)),
+        Err(_) => Err(Error::encode("jpeg", "mozjpeg internal error")),
+    }
+}
+
+// Qualité 0-100 -> distance butteraugli de libjxl (même courbe que JxlEncoderDistanceFromQuality)
+fn jxl_distance(quality: u8) -> f32 {
+    let q = quality as f32;
+    if q >= 30.0 {
+        0.1 + (100.0 - q) * 0.09
+    } else {
+        53.0 / 3000.0 * q * q - 23.0 / 20.0 * q + 25.0
+    }
+}
+
+#[cfg(feature = "jxl")]
+fn jxl_speed(effort: u8) -> jpegxl_rs::encode::EncoderSpeed {
+    use jpegxl_rs::encode::EncoderSpeed;
+    match effort {
+        0 | 1 => EncoderSpeed::Lightning,
+        2 => EncoderSpeed::Thunder,
+        3 => EncoderSpeed::Falcon,
+        4 => EncoderSpeed::Cheetah,
+        5 => EncoderSpeed::Hare,
+        6 => EncoderSpeed::Wombat,
+        7 => EncoderSpeed::Squirrel,
+        8 => EncoderSpeed::Kitten,
+        _ => EncoderSpeed::Tortoise,
+    }
+}
+
+// Encodage des pixels, en RGB ou RGBA selon la présence d'alpha. Sans perte, une source
+// 16 bits est encodée en 16 bits ; les flottants n'ont pas d'équivalent entier exact
+#[cfg(feature = "jxl")]
+fn encode_jxl_pixels(img: &DynamicImage, distance: f32, lossless: bool, jxl: &JxlOptions) -> Result<Vec<u8>> {
+    let has_alpha = img.color().has_alpha();
+    let sixteen_bits = lossless && is_high_depth(img);
+    if sixteen_bits && matches!(img, DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_)) {
+        return Err(Error::UnsupportedFormat {
+            details: format!("jxl lossless does not support floating-point input ({:?})", img.color()),
+        });
+    }
+
+    let mut encoder = jpegxl_rs::encoder_builder()
+        .has_alpha(has_alpha)
+        .lossless(lossless)
+        .uses_original_profile(lossless)
+        .quality(distance)
+        .speed(jxl_speed(jxl.effort))
+        .build()
+        .map_err(|e| Error::encode("jxl", e))?;
+    if sixteen_bits {
+        let pixels = if has_alpha { img.to_rgba16().into_raw() } else { img.to_rgb16().into_raw() };
+        let result: jpegxl_rs::encode::EncoderResult<u16> =
+            encoder.encode::<u16, u16>(&pixels, img.width(), img.height()).map_err(|e| Error::encode("jxl", e))?;
+        return Ok(result.data);
+    }
+    let pixels = if has_alpha { img.to_rgba8().into_raw() } else { img.to_rgb8().into_raw() };
+    let result: jpegxl_rs::encode::EncoderResult<u8> =
+        encoder.encode::<u8, u8>(&pixels, img.width(), img.height()).map_err(|e| Error::encode("jxl", e))?;
+    Ok(result.data)
+}
+
+#[cfg(not(feature = "jxl"))]
+fn encode_jxl_pixels(_img: &DynamicImage, _distance: f32, _lossless: bool, _jxl: &JxlOptions) -> Result<Vec<u8>> {
+    Err(Error::not_compiled("jxl", "jxl"))
+}
+
+pub fn encode_jxl(img: &DynamicImage, quality: u8, jxl: &JxlOptions) -> Result<Vec<u8>> {
+    encode_jxl_pixels(img, jxl_distance(quality), false, jxl)
+}
+
+// JPEG XL sans perte sur les pixels (modular)
+pub fn encode_jxl_lossless(img: &DynamicImage, jxl: &JxlOptions) -> Result<Vec<u8>> {
+    encode_jxl_pixels(img, 0.0, true, jxl)
+}
+
+// Transcodage d'un JPEG existant : les coefficients DCT sont repris tels quels et le conteneur
+// garde de quoi reconstruire le fichier JPEG d'origine à l'octet près (~20 % plus léger)
+#[cfg(feature = "jxl")]
+pub fn encode_jxl_from_jpeg(jpeg: &[u8], jxl: &JxlOptions) -> Result<Vec<u8>> {
+    let mut encoder = jpegxl_rs::encoder_builder()
+        .use_container(true)
+        .speed(jxl_speed(jxl.effort))
+        .build()
+        .map_err(|e| Error::encode("jxl", e))?;
+    let result: jpegxl_rs::encode::EncoderResult<u8> =
+        encoder.encode_jpeg(jpeg).map_err(|e| Error::encode("jxl", e))?;
+    Ok(result.data)
+}
+
+#[cfg(not(feature = "jxl"))]
+pub fn encode_jxl_from_jpeg(_jpeg: &[u8], _jxl: &JxlOptions) -> Result<Vec<u8>> {
+    Err(Error::not_compiled("jxl", "jxl"))
+}
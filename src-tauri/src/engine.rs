@@ -0,0 +1,579 @@
+/*
+ * Copyright (C) 2026  Romain Lathuiliere
+ * * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ */
+
+use image::imageops::FilterType;
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Instant;
+
+use crate::animation::{
+    check_animation_support, decode_animation, encode_animation, frame_count, frame_loss_warning, handles_animation,
+};
+use crate::auto::encode_best;
+use crate::control::BatchControl;
+use crate::decode::open_image;
+use crate::encoders::{
+    encode_avif, encode_avif_lossless, encode_jpeg, encode_jxl, encode_jxl_from_jpeg, encode_jxl_lossless, encode_png,
+    encode_png_lossless, encode_webp, encode_webp_lossless,
+};
+use crate::error::{Error, Result};
+use crate::format::{check_range, AvifOptions, JpegOptions, JxlOptions, OutputFormat, PngOptions, WebpOptions};
+use crate::search::{best_quality_under, fit_to_size, fit_to_ssim, Encoded};
+
+/// Que faire quand l'image encodée est plus lourde que la source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IfLarger {
+    // On écrit quand même la sortie (comportement historique)
+    #[default]
+    Keep,
+    // On copie la source telle quelle dans le dossier de sortie
+    Copy,
+    // On n'écrit rien
+    Skip,
+    // On baisse la qualité jusqu'à passer sous la source, sinon on copie la source
+    Retry,
+}
+
+impl FromStr for IfLarger {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "keep" => Ok(IfLarger::Keep),
+            "copy" => Ok(IfLarger::Copy),
+            "skip" => Ok(IfLarger::Skip),
+            "retry" => Ok(IfLarger::Retry),
+            other => Err(Error::InvalidConfig { details: format!("unknown if-larger policy: {}", other) }),
+        }
+    }
+}
+
+/// Réglages d'encodage d'une image, indépendants de Tauri.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Options {
+    pub output_dir: PathBuf,
+    pub format: OutputFormat,
+    pub quality: u8,
+    pub max_width: Option<u32>,
+    pub max_height: Option<u32>,
+    // Sortie sans perte (WebP, PNG, AVIF) : la qualité est ignorée
+    #[serde(default)]
+    pub lossless: bool,
+    // Mode "taille cible" : budget en octets par image, la qualité devient un plafond
+    #[serde(default)]
+    pub target_size: Option<u64>,
+    // Mode "qualité perceptuelle" : SSIM minimal (0-1) contre l'image redimensionnée
+    #[serde(default)]
+    pub min_ssim: Option<f64>,
+    #[serde(default)]
+    pub avif: AvifOptions,
+    #[serde(default)]
+    pub png: PngOptions,
+    #[serde(default)]
+    pub webp: WebpOptions,
+    #[serde(default)]
+    pub jpeg: JpegOptions,
+    #[serde(default)]
+    pub jxl: JxlOptions,
+    // Garde-fou contre les sorties plus lourdes que la source
+    #[serde(default)]
+    pub if_larger: IfLarger,
+    // Gain minimal exigé (en % et/ou en octets), sinon la source est gardée
+    #[serde(default)]
+    pub min_savings_percent: Option<f64>,
+    #[serde(default)]
+    pub min_savings_bytes: Option<u64>,
+    // Gain insuffisant : copier la source dans le dossier de sortie plutôt que ne rien écrire
+    #[serde(default)]
+    pub copy_skipped: bool,
+}
+
+impl Options {
+    /// Vérifie les réglages avant de lancer quoi que ce soit.
+    pub fn validate(&self) -> Result<()> {
+        check_range("quality", self.quality, 0, 100)?;
+        if self.max_width == Some(0) || self.max_height == Some(0) {
+            return Err(Error::InvalidConfig { details: "max width/height must be greater than 0".to_string() });
+        }
+        if self.lossless {
+            if self.format == OutputFormat::Jpg {
+                return Err(Error::InvalidConfig { details: "JPEG has no lossless mode".to_string() });
+            }
+            if self.target_size.is_some() || self.min_ssim.is_some() {
+                return Err(Error::InvalidConfig {
+                    details: "lossless cannot be combined with target size or min SSIM".to_string(),
+                });
+            }
+        }
+        if self.target_size == Some(0) {
+            return Err(Error::InvalidConfig { details: "target size must be greater than 0".to_string() });
+        }
+        if let Some(min_ssim) = self.min_ssim {
+            if !(0.0..=1.0).contains(&min_ssim) {
+                return Err(Error::InvalidConfig { details: format!("min SSIM must be between 0 and 1 (got {})", min_ssim) });
+            }
+            if self.target_size.is_some() {
+                return Err(Error::InvalidConfig { details: "target size and min SSIM cannot be combined".to_string() });
+            }
+            // Le SSIM se mesure sur la sortie relue, et `image` ne décode pas le JPEG XL
+            if self.format == OutputFormat::Jxl {
+                return Err(Error::InvalidConfig { details: "min SSIM is not available for JPEG XL".to_string() });
+            }
+            // ... ni l'AVIF sans dav1d
+            if self.format == OutputFormat::Avif && !cfg!(feature = "avif-decode") {
+                return Err(Error::not_compiled("AVIF decoding (min SSIM)", "avif-decode"));
+            }
+        }
+        if let Some(percent) = self.min_savings_percent {
+            if !(0.0..=100.0).contains(&percent) {
+                return Err(Error::InvalidConfig { details: format!("min savings must be between 0 and 100% (got {})", percent) });
+            }
+        }
+        // Les recherches de qualité et les cibles libwebp se contrediraient
+        if self.webp.has_target() && (self.target_size.is_some() || self.min_ssim.is_some()) {
+            return Err(Error::InvalidConfig {
+                details: "webp target size/PSNR cannot be combined with target size or min SSIM".to_string(),
+            });
+        }
+        if self.format == OutputFormat::Jxl && !cfg!(feature = "jxl") {
+            return Err(Error::not_compiled("jxl", "jxl"));
+        }
+        self.avif.validate()?;
+        self.webp.validate()?;
+        self.jpeg.validate()?;
+        self.jxl.validate()?;
+        self.png.validate(self.quality)
+    }
+
+    /// Copie des réglages avec une autre qualité (utilisé par les recherches automatiques).
+    pub fn with_quality(&self, quality: u8) -> Options {
+        let mut options = self.clone();
+        options.quality = quality;
+        options.png.min_quality = options.png.min_quality.map(|min| min.min(quality));
+        options
+    }
+}
+
+/// Raison pour laquelle l'encodage a été écarté au profit de la source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    // L'encodage (de `encoded_size` octets) dépassait la taille de la source
+    Larger { encoded_size: u64 },
+    // Le gain n'atteignait pas `min_savings_percent` / `min_savings_bytes`
+    BelowThreshold { encoded_size: u64 },
+}
+
+impl SkipReason {
+    /// Statut reporté dans `ProcessResult`.
+    pub fn status(self) -> &'static str {
+        match self {
+            SkipReason::Larger { .. } => "skipped-larger",
+            SkipReason::BelowThreshold { .. } => "skipped-threshold",
+        }
+    }
+
+    pub fn encoded_size(self) -> u64 {
+        match self {
+            SkipReason::Larger { encoded_size } | SkipReason::BelowThreshold { encoded_size } => encoded_size,
+        }
+    }
+}
+
+/// Résultat d'une compression réussie.
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub original: PathBuf,
+    // Absent si l'image a été écartée sans copie
+    pub output: Option<PathBuf>,
+    pub original_size: u64,
+    pub output_size: Option<u64>,
+    pub input_width: u32,
+    pub input_height: u32,
+    pub output_width: u32,
+    pub output_height: u32,
+    // Format réellement écrit (jamais `Auto`)
+    pub format: OutputFormat,
+    pub quality: u8,
+    // En format automatique : pourquoi ce format a gagné
+    pub format_reason: Option<String>,
+    // Perte signalée sans faire échouer l'image (animation aplatie en image fixe)
+    pub warning: Option<String>,
+    // Temps d'encodage seul (hors décodage et écriture)
+    pub encode_ms: u64,
+    // Renseigné si l'encodage a été écarté (la source a éventuellement été copiée)
+    pub skipped: Option<SkipReason>,
+}
+
+/// Estimation de taille calculée sur un proxy basse résolution.
+#[derive(Debug, Clone)]
+pub struct Estimate {
+    pub original_size: u64,
+    pub estimated_size: u64,
+}
+
+/// Encode une image en mémoire avec l'encodeur adapté au format demandé.
+pub fn encode_image(img: &DynamicImage, options: &Options) -> Result<Vec<u8>> {
+    if options.lossless {
+        return match options.format {
+            OutputFormat::Webp => encode_webp_lossless(img, &options.webp),
+            OutputFormat::Avif => encode_avif_lossless(img, &options.avif),
+            OutputFormat::Png => encode_png_lossless(img),
+            OutputFormat::Jxl => encode_jxl_lossless(img, &options.jxl),
+            OutputFormat::Jpg => Err(Error::InvalidConfig { details: "JPEG has no lossless mode".to_string() }),
+            OutputFormat::Auto => Err(auto_not_resolved()),
+        };
+    }
+    match options.format {
+        OutputFormat::Webp => encode_webp(img, options.quality, &options.webp),
+        OutputFormat::Avif => encode_avif(img, options.quality, &options.avif),
+        OutputFormat::Png => encode_png(img, options.quality, &options.png),
+        OutputFormat::Jpg => encode_jpeg(img, options.quality, &options.jpeg),
+        OutputFormat::Jxl => encode_jxl(img, options.quality, &options.jxl),
+        OutputFormat::Auto => Err(auto_not_resolved()),
+    }
+}
+
+// `Auto` passe par `encode_best`, qui appelle `encode_image` avec chaque format candidat
+fn auto_not_resolved() -> Error {
+    Error::UnsupportedFormat { details: "auto must be resolved to a concrete format before encoding".to_string() }
+}
+
+/// Encode selon le mode demandé : format automatique, taille cible, SSIM minimal ou qualité fixe.
+pub(crate) fn encode_with_mode(img: DynamicImage, options: &Options) -> Result<Encoded> {
+    match (options.format, options.target_size, options.min_ssim) {
+        (OutputFormat::Auto, _, _) => encode_best(img, options),
+        (_, Some(budget), _) => fit_to_size(img, options, budget),
+        (_, None, Some(min_ssim)) => fit_to_ssim(img, options, min_ssim).map(|(encoded, _)| encoded),
+        (_, None, None) => {
+            let data = encode_image(&img, options)?;
+            Ok(Encoded::new(img, data, options))
+        }
+    }
+}
+
+/// Dimensions finales après application des max_width / max_height (jamais d'agrandissement).
+pub fn target_dimensions(width: u32, height: u32, max_width: Option<u32>, max_height: Option<u32>) -> (u32, u32) {
+    let target_w = max_width.unwrap_or(u32::MAX);
+    let target_h = max_height.unwrap_or(u32::MAX);
+
+    let scale = (target_w as f64 / width as f64).min(target_h as f64 / height as f64).min(1.0);
+    ((width as f64 * scale) as u32, (height as f64 * scale) as u32)
+}
+
+pub fn resize_to_fit(img: DynamicImage, max_width: Option<u32>, max_height: Option<u32>) -> DynamicImage {
+    let (w, h) = (img.width(), img.height());
+    let tw = max_width.unwrap_or(u32::MAX);
+    let th = max_height.unwrap_or(u32::MAX);
+
+    if w > tw || h > th {
+        img.resize(tw, th, FilterType::Lanczos3)
+    } else {
+        img
+    }
+}
+
+// Fonction helper pour éviter d'écraser les fichiers existants
+pub fn get_unique_path(mut path: PathBuf) -> PathBuf {
+    let mut counter = 1;
+    let original_stem = path.file_stem().unwrap().to_string_lossy().to_string();
+    let extension = path.extension().unwrap().to_string_lossy().to_string();
+    let parent = path.parent().unwrap().to_path_buf();
+
+    // Tant que le fichier existe, on ajoute un numéro -1, -2, etc.
+    while path.exists() {
+        let new_name = format!("{}-{}.{}", original_stem, counter, extension);
+        path = parent.join(new_name);
+        counter += 1;
+    }
+
+    path
+}
+
+/// Chemin de sortie "théorique" (avant dédoublonnage) pour une image source.
+pub fn output_path_for(path: &Path, output_dir: &Path, format: OutputFormat) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    output_dir.join(format!("{}-compressed.{}", stem, format.extension()))
+}
+
+/// Compresse un fichier : décodage, redimensionnement, encodage puis écriture dans `output_dir`.
+pub fn compress_file(path: &Path, options: &Options) -> Result<Outcome> {
+    compress_file_with_control(path, options, &BatchControl::new())?.ok_or(Error::Cancelled)
+}
+
+/// Comme `compress_file`, mais abandonne l'image (`Ok(None)`) si le batch est annulé
+/// pendant l'encodage : rien n'est alors écrit sur le disque.
+pub fn compress_file_with_control(path: &Path, options: &Options, control: &BatchControl) -> Result<Option<Outcome>> {
+    let original_size = fs::metadata(path)?.len();
+    // GIF / WebP animés : images comptées sans décodage, puis toutes décodées seulement
+    // si la sortie est animée (sinon la première suffit)
+    let frames = frame_count(path);
+    check_animation_support(path, frames, options.format)?;
+    let animation = if frames > 1 && handles_animation(options.format) { decode_animation(path)? } else { None };
+
+    let started;
+    let mut warning = None;
+    let (input_width, input_height, encoded) = match animation {
+        Some(animation) => {
+            if animation.frame_count() < frames {
+                warning = Some(format!("only {} of {} frames could be decoded", animation.frame_count(), frames));
+            }
+            let (width, height) = animation.dimensions();
+            started = Instant::now();
+            (width, height, encode_animation(animation, options)?)
+        }
+        None => {
+            if frames > 1 {
+                warning = Some(if handles_animation(options.format) {
+                    format!("animated input ({} frames): only the first frame could be decoded", frames)
+                } else {
+                    frame_loss_warning(frames, options.format)
+                });
+            }
+            let img = open_image(path)?;
+            let (width, height) = (img.width(), img.height());
+            let final_img = resize_to_fit(img, options.max_width, options.max_height);
+            started = Instant::now();
+            (width, height, encode_still(path, final_img, (width, height), options, original_size)?)
+        }
+    };
+    let encode_ms = started.elapsed().as_millis() as u64;
+    if control.is_cancelled() {
+        return Ok(None);
+    }
+
+    let Encoded { image: final_img, data, quality, format, reason } = encoded;
+    let mut outcome = Outcome {
+        original: path.to_path_buf(),
+        output: None,
+        original_size,
+        output_size: None,
+        input_width,
+        input_height,
+        output_width: final_img.width(),
+        output_height: final_img.height(),
+        format,
+        quality,
+        format_reason: reason,
+        warning,
+        encode_ms,
+        skipped: None,
+    };
+
+    let encoded_size = data.len() as u64;
+    // Garder la source n'a de sens qu'à dimensions égales : une image redimensionnée est
+    // toujours écrite, sinon max_width / max_height seraient ignorés
+    let resized = (outcome.output_width, outcome.output_height) != (input_width, input_height);
+    let skipped = if resized {
+        None
+    } else if encoded_size > original_size && options.if_larger != IfLarger::Keep {
+        Some((SkipReason::Larger { encoded_size }, options.if_larger != IfLarger::Skip))
+    } else if !meets_min_savings(original_size, encoded_size, options) {
+        Some((SkipReason::BelowThreshold { encoded_size }, options.copy_skipped))
+    } else {
+        None
+    };
+    if let Some((reason, copy)) = skipped {
+        outcome.skipped = Some(reason);
+        if copy {
+            outcome.output = Some(copy_original(path, &options.output_dir)?);
+            outcome.output_size = Some(original_size);
+        }
+        // Ce qui reste, c'est la source : ses dimensions, pas celles de l'encodage écarté
+        outcome.output_width = input_width;
+        outcome.output_height = input_height;
+        return Ok(Some(outcome));
+    }
+
+    // UX SECURITY : On vérifie si la sortie existe et on renomme si besoin
+    let output_path = get_unique_path(output_path_for(path, &options.output_dir, format));
+    write_output(&output_path, &data)?;
+    outcome.output = Some(output_path);
+    outcome.output_size = Some(data.len() as u64);
+    Ok(Some(outcome))
+}
+
+// Chaque seuil configuré doit être atteint ; une sortie plus lourde n'en atteint aucun
+fn meets_min_savings(original_size: u64, encoded_size: u64, options: &Options) -> bool {
+    let saved = original_size.saturating_sub(encoded_size);
+    let percent_ok = options.min_savings_percent.is_none_or(|min| {
+        original_size > 0 && saved as f64 * 100.0 / original_size as f64 >= min
+    });
+    let bytes_ok = options.min_savings_bytes.is_none_or(|min| saved >= min);
+    percent_ok && bytes_ok
+}
+
+fn encode_still(
+    path: &Path,
+    final_img: DynamicImage,
+    input_dimensions: (u32, u32),
+    options: &Options,
+    original_size: u64,
+) -> Result<Encoded> {
+    // JPEG XL sans perte d'un JPEG non redimensionné : transcodage réversible à l'octet près
+    let jpeg_source = if options.format == OutputFormat::Jxl
+        && options.lossless
+        && (final_img.width(), final_img.height()) == input_dimensions
+    {
+        Some(fs::read(path)?).filter(|bytes| image::guess_format(bytes).ok() == Some(image::ImageFormat::Jpeg))
+    } else {
+        None
+    };
+    let mut encoded = match jpeg_source {
+        Some(jpeg) => {
+            let data = encode_jxl_from_jpeg(&jpeg, &options.jxl)?;
+            Encoded::new(final_img, data, options)
+        }
+        None => encode_with_mode(final_img, options)?,
+    };
+    if options.if_larger == IfLarger::Retry && encoded.data.len() as u64 > original_size {
+        encoded = retry_smaller(encoded, options, original_size)?;
+    }
+    Ok(encoded)
+}
+
+// Nouvel essai sous la taille de la source en ne jouant que sur la qualité (dimensions
+// inchangées). Sans perte, il n'y a pas de qualité à baisser : on garde l'encodage.
+fn retry_smaller(encoded: Encoded, options: &Options, original_size: u64) -> Result<Encoded> {
+    if options.lossless || original_size == 0 {
+        return Ok(encoded);
+    }
+    // Même format que le premier essai (en mode auto : le gagnant)
+    let retry_options = Options { format: encoded.format, ..options.with_quality(encoded.quality) };
+    match best_quality_under(&encoded.image, &retry_options, original_size - 1)? {
+        Some((data, quality)) => Ok(Encoded { data, quality, ..encoded }),
+        None => Ok(encoded),
+    }
+}
+
+// Copie de la source dans le dossier de sortie, avec son extension d'origine
+fn copy_original(path: &Path, output_dir: &Path) -> Result<PathBuf> {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path.extension().unwrap_or_default().to_string_lossy();
+    let output_path = get_unique_path(output_dir.join(format!("{}-compressed.{}", stem, extension)));
+    write_output(&output_path, &fs::read(path)?)?;
+    Ok(output_path)
+}
+
+// Écriture atomique : on passe par un fichier .part renommé à la fin,
+// pour ne jamais laisser de sortie tronquée (erreur disque, annulation, crash).
+fn write_output(output_path: &Path, data: &[u8]) -> Result<()> {
+    let mut part_name = output_path.as_os_str().to_owned();
+    part_name.push(".part");
+    let part_path = PathBuf::from(part_name);
+
+    let res = fs::write(&part_path, data).and_then(|_| fs::rename(&part_path, output_path));
+    if let Err(e) = res {
+        let _ = fs::remove_file(&part_path);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Estime la taille de sortie sans écrire sur le disque.
+pub fn estimate_file(path: &Path, options: &Options) -> Result<Estimate> {
+    let metadata = fs::metadata(path)?;
+    let original_disk_size = metadata.len();
+
+    let img = open_image(path)?;
+    let (final_w, final_h) = target_dimensions(img.width(), img.height(), options.max_width, options.max_height);
+    let resized = (final_w, final_h) != (img.width(), img.height());
+
+    // --- ESTIMATION ---
+    // On compresse un proxy de 256px puis on extrapole à la surface finale
+    let proxy_size = 256;
+    let (proxy_img, ratio) = if final_w > proxy_size {
+        let proxy = img.resize(proxy_size, proxy_size, FilterType::Triangle);
+        let area_final = final_w as f64 * final_h as f64;
+        let area_proxy = proxy.width() as f64 * proxy.height() as f64;
+        (proxy, area_final / area_proxy)
+    } else {
+        (img.resize(final_w, final_h, FilterType::Triangle), 1.0)
+    };
+
+    // Animation gardée en WebP : borne haute, chaque image estimée comme une image fixe
+    let frames = frame_count(path);
+    check_animation_support(path, frames, options.format)?;
+    let size = match options.format {
+        format if frames > 1 && handles_animation(format) => {
+            let webp_options = Options { format: OutputFormat::Webp, ..options.clone() };
+            encode_image(&proxy_img, &webp_options)?.len() as u64 * frames as u64
+        }
+        OutputFormat::Auto => encode_best(proxy_img, options)?.data.len() as u64,
+        _ => encode_image(&proxy_img, options)?.len() as u64,
+    };
+    let mut estimated_size = (size as f64 * ratio) as u64;
+    // En mode taille cible, la sortie ne dépassera jamais le budget
+    if let Some(budget) = options.target_size {
+        estimated_size = estimated_size.min(budget);
+    }
+    // Une sortie plus lourde que la source sera écartée : on garde au pire la source
+    if !resized && options.if_larger != IfLarger::Keep {
+        estimated_size = estimated_size.min(original_disk_size);
+    }
+    // Gain sous le seuil : la source sera gardée telle quelle (si non redimensionnée)
+    if !resized && !meets_min_savings(original_disk_size, estimated_size, options) {
+        estimated_size = original_disk_size;
+    }
+
+    Ok(Estimate {
+        original_size: original_disk_size,
+        estimated_size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn savings_options(percent: Option<f64>, bytes: Option<u64>) -> Options {
+        let mut options: Options =
+            serde_json::from_value(serde_json::json!({ "output_dir": ".", "format": "webp", "quality": 80 })).unwrap();
+        options.min_savings_percent = percent;
+        options.min_savings_bytes = bytes;
+        options
+    }
+
+    #[test]
+    fn no_threshold_accepts_any_output() {
+        let options = savings_options(None, None);
+        assert!(meets_min_savings(1000, 999, &options));
+        assert!(meets_min_savings(1000, 2000, &options));
+    }
+
+    #[test]
+    fn percent_threshold_is_inclusive() {
+        let options = savings_options(Some(10.0), None);
+        assert!(meets_min_savings(1000, 900, &options));
+        assert!(!meets_min_savings(1000, 901, &options));
+    }
+
+    #[test]
+    fn byte_threshold_is_inclusive() {
+        let options = savings_options(None, Some(100));
+        assert!(meets_min_savings(1000, 900, &options));
+        assert!(!meets_min_savings(1000, 901, &options));
+    }
+
+    #[test]
+    fn every_configured_threshold_must_be_met() {
+        let options = savings_options(Some(50.0), Some(100));
+        assert!(!meets_min_savings(1000, 800, &options));
+        assert!(!meets_min_savings(100, 40, &options));
+        assert!(meets_min_savings(1000, 400, &options));
+    }
+
+    #[test]
+    fn larger_output_or_empty_source_never_meets_a_threshold() {
+        assert!(!meets_min_savings(1000, 1200, &savings_options(None, Some(1))));
+        assert!(!meets_min_savings(0, 0, &savings_options(Some(0.0), None)));
+    }
+}
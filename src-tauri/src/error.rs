@@ -0,0 +1,131 @@
+/*
+ * Copyright (C) 2026  Romain Lathuiliere
+ * * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ */
+
+use image::ImageError;
+use serde::Serialize;
+use std::any::Any;
+use std::io;
+
+/// Erreurs du moteur. Sérialisées avec un `code` stable sur lequel le frontend peut
+/// s'appuyer, ex : `{ "code": "encode", "codec": "webp", "details": "..." }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, thiserror::Error)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum Error {
+    #[error("Could not decode image: {details}")]
+    Decode { details: String },
+
+    #[error("Unsupported format: {details}")]
+    UnsupportedFormat { details: String },
+
+    #[error("Invalid settings: {details}")]
+    InvalidConfig { details: String },
+
+    #[error("{codec} encoding failed: {details}")]
+    Encode { codec: String, details: String },
+
+    #[error("Target not reached: {details}")]
+    TargetNotReached { details: String },
+
+    #[error("I/O error: {details}")]
+    Io { details: String },
+
+    #[error("Permission denied: {details}")]
+    PermissionDenied { details: String },
+
+    #[error("Not enough disk space: {details}")]
+    OutOfSpace { details: String },
+
+    // Requête sur un élément inexistant (journal, job)
+    #[error("Not found: {details}")]
+    NotFound { details: String },
+
+    // Requête valide mais prématurée (ex : rapport d'un batch encore en cours)
+    #[error("Invalid state: {details}")]
+    InvalidState { details: String },
+
+    #[error("Internal error: {details}")]
+    Internal { details: String },
+
+    #[error("Cancelled")]
+    Cancelled,
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    pub fn encode(codec: &str, details: impl ToString) -> Self {
+        Error::Encode { codec: codec.to_string(), details: details.to_string() }
+    }
+
+    /// Code stable, identique au champ `code` sérialisé.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Decode { .. } => "decode",
+            Error::UnsupportedFormat { .. } => "unsupported_format",
+            Error::InvalidConfig { .. } => "invalid_config",
+            Error::Encode { .. } => "encode",
+            Error::TargetNotReached { .. } => "target_not_reached",
+            Error::Io { .. } => "io",
+            Error::PermissionDenied { .. } => "permission_denied",
+            Error::OutOfSpace { .. } => "out_of_space",
+            Error::NotFound { .. } => "not_found",
+            Error::InvalidState { .. } => "invalid_state",
+            Error::Internal { .. } => "internal",
+            Error::Cancelled => "cancelled",
+        }
+    }
+
+    /// Panic rattrapé pendant le traitement d'une image (bug d'un codec, assertion interne).
+    pub fn panic(payload: &(dyn Any + Send)) -> Self {
+        let details = match payload.downcast_ref::<&str>() {
+            Some(msg) => msg.to_string(),
+            None => payload.downcast_ref::<String>().cloned().unwrap_or_else(|| "panic".to_string()),
+        };
+        Error::Internal { details }
+    }
+
+    /// Format dont le codec n'a pas été compilé (fonctionnalité cargo désactivée).
+    pub fn not_compiled(format: &str, feature: &str) -> Self {
+        Error::UnsupportedFormat {
+            details: format!("{} support is not compiled in (cargo feature \"{}\")", format, feature),
+        }
+    }
+
+    /// Erreur à l'ouverture / au décodage d'une image source.
+    pub fn decode(err: ImageError) -> Self {
+        match err {
+            ImageError::IoError(e) => e.into(),
+            ImageError::Unsupported(e) => Error::UnsupportedFormat { details: e.to_string() },
+            other => Error::Decode { details: other.to_string() },
+        }
+    }
+}
+
+// ENOSPC (unix) / ERROR_DISK_FULL, ERROR_HANDLE_DISK_FULL (windows)
+fn is_out_of_space(err: &io::Error) -> bool {
+    match err.raw_os_error() {
+        #[cfg(unix)]
+        Some(28) => true,
+        #[cfg(windows)]
+        Some(39) | Some(112) => true,
+        _ => false,
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        let details = err.to_string();
+        if err.kind() == io::ErrorKind::PermissionDenied {
+            Error::PermissionDenied { details }
+        } else if is_out_of_space(&err) {
+            Error::OutOfSpace { details }
+        } else {
+            Error::Io { details }
+        }
+    }
+}
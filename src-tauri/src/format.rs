@@ -0,0 +1,394 @@
+/*
+ * Copyright (C) 2026  Romain Lathuiliere
+ * * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ */
+
+// Formats de sortie et réglages propres à chaque encodeur.
+// Tout est validé avant le lancement du batch : pas de JPEG "surprise" sur un format inconnu.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[serde(alias = "jpeg")]
+    Jpg,
+    Png,
+    Webp,
+    Avif,
+    #[serde(alias = "jpegxl")]
+    Jxl,
+    // Choix par image du plus petit candidat à qualité visuelle équivalente
+    Auto,
+}
+
+impl OutputFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OutputFormat::Jpg => "jpg",
+            OutputFormat::Png => "png",
+            OutputFormat::Webp => "webp",
+            OutputFormat::Avif => "avif",
+            OutputFormat::Jxl => "jxl",
+            OutputFormat::Auto => "auto",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        self.as_str()
+    }
+
+    // Format `image` correspondant, pour relire une sortie encodée
+    // (aucun pour `Auto`, et `image` ne sait pas décoder le JPEG XL)
+    pub fn image_format(self) -> Option<image::ImageFormat> {
+        match self {
+            OutputFormat::Jpg => Some(image::ImageFormat::Jpeg),
+            OutputFormat::Png => Some(image::ImageFormat::Png),
+            OutputFormat::Webp => Some(image::ImageFormat::WebP),
+            OutputFormat::Avif => Some(image::ImageFormat::Avif),
+            OutputFormat::Jxl | OutputFormat::Auto => None,
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "jpg" | "jpeg" => Ok(OutputFormat::Jpg),
+            "png" => Ok(OutputFormat::Png),
+            "webp" => Ok(OutputFormat::Webp),
+            "avif" => Ok(OutputFormat::Avif),
+            "jxl" | "jpegxl" => Ok(OutputFormat::Jxl),
+            "auto" => Ok(OutputFormat::Auto),
+            other => Err(Error::UnsupportedFormat { details: other.to_string() }),
+        }
+    }
+}
+
+/// Profondeur de couleur interne de l'AVIF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AvifBitDepth {
+    #[serde(rename = "8")]
+    Eight,
+    // Moins de banding dans les dégradés, au prix de quelques octets
+    #[serde(rename = "10")]
+    Ten,
+}
+
+/// Modèle de couleur interne de l'AVIF.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AvifColorModel {
+    // Standard, le plus compact
+    #[default]
+    YCbCr,
+    // Pas de sous-échantillonnage chroma : couleurs plus fidèles, fichier plus lourd
+    Rgb,
+}
+
+/// Traitement des couleurs sous les pixels transparents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AvifAlphaMode {
+    // Couleurs invisibles nettoyées pour mieux compresser
+    #[default]
+    Clean,
+    // Couleurs conservées telles quelles
+    Dirty,
+    Premultiplied,
+}
+
+/// Réglages AVIF (ravif).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AvifOptions {
+    // 1 = le plus lent / le plus petit, 10 = le plus rapide
+    pub speed: u8,
+    // Qualité du canal alpha (défaut : choisie par ravif d'après la qualité)
+    pub alpha_quality: Option<u8>,
+    // Défaut : choix de ravif
+    pub bit_depth: Option<AvifBitDepth>,
+    pub color_model: AvifColorModel,
+    pub alpha_mode: AvifAlphaMode,
+}
+
+impl Default for AvifOptions {
+    fn default() -> Self {
+        // speed(4) = Bon compromis vitesse/taille
+        AvifOptions {
+            speed: 4,
+            alpha_quality: None,
+            bit_depth: None,
+            color_model: AvifColorModel::default(),
+            alpha_mode: AvifAlphaMode::default(),
+        }
+    }
+}
+
+impl FromStr for AvifBitDepth {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "8" => Ok(AvifBitDepth::Eight),
+            "10" => Ok(AvifBitDepth::Ten),
+            other => Err(invalid_choice("avif.bit_depth", other)),
+        }
+    }
+}
+
+impl FromStr for AvifColorModel {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "ycbcr" => Ok(AvifColorModel::YCbCr),
+            "rgb" => Ok(AvifColorModel::Rgb),
+            other => Err(invalid_choice("avif.color_model", other)),
+        }
+    }
+}
+
+impl FromStr for AvifAlphaMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "clean" => Ok(AvifAlphaMode::Clean),
+            "dirty" => Ok(AvifAlphaMode::Dirty),
+            "premultiplied" => Ok(AvifAlphaMode::Premultiplied),
+            other => Err(invalid_choice("avif.alpha_mode", other)),
+        }
+    }
+}
+
+fn invalid_choice(name: &str, value: &str) -> Error {
+    Error::InvalidConfig { details: format!("invalid {}: {}", name, value) }
+}
+
+/// Réglages WebP avec perte (libwebp `WebPConfig`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebpOptions {
+    // Effort : 0 = rapide, 6 = plus lent et plus petit
+    pub method: u8,
+    pub alpha_quality: u8,
+    // Mode near-lossless (0 = prétraitement max, 100 = aucun) : encodage VP8L,
+    // la qualité règle alors l'effort de compression
+    pub near_lossless: Option<u8>,
+    // Garde les couleurs sous les pixels transparents
+    pub exact: bool,
+    // Conversion RGB -> YUV plus précise : limite les bavures sur le texte rouge
+    pub sharp_yuv: bool,
+    // Cibles libwebp (octets ou dB), atteintes en `passes` passes
+    pub target_size: Option<u64>,
+    pub target_psnr: Option<f32>,
+    pub passes: u8,
+}
+
+impl Default for WebpOptions {
+    fn default() -> Self {
+        // Valeurs par défaut de libwebp
+        WebpOptions {
+            method: 4,
+            alpha_quality: 100,
+            near_lossless: None,
+            exact: false,
+            sharp_yuv: false,
+            target_size: None,
+            target_psnr: None,
+            passes: 1,
+        }
+    }
+}
+
+/// Sous-échantillonnage de la chrominance JPEG.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JpegSubsampling {
+    // Couleurs pleine résolution : texte et aplats nets
+    #[serde(rename = "444")]
+    S444,
+    #[serde(rename = "422")]
+    S422,
+    // Standard photo, le plus compact
+    #[default]
+    #[serde(rename = "420")]
+    S420,
+}
+
+impl FromStr for JpegSubsampling {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        // "4:2:0" comme "420"
+        match s.replace(':', "").as_str() {
+            "444" => Ok(JpegSubsampling::S444),
+            "422" => Ok(JpegSubsampling::S422),
+            "420" => Ok(JpegSubsampling::S420),
+            _ => Err(invalid_choice("jpeg.subsampling", s)),
+        }
+    }
+}
+
+/// Réglages JPEG (mozjpeg).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JpegOptions {
+    pub progressive: bool,
+    pub subsampling: JpegSubsampling,
+    // Tables de Huffman calculées pour chaque image ; toujours actif en progressif (libjpeg l'impose)
+    pub optimize_huffman: bool,
+    // Quantification trellis. Indépendante du progressif, mais le crate mozjpeg ne sait produire
+    // du baseline qu'avec `set_fastest_defaults`, qui la désactive : on ne la garde qu'en progressif
+    pub trellis: bool,
+}
+
+impl Default for JpegOptions {
+    fn default() -> Self {
+        // Profil par défaut de mozjpeg
+        JpegOptions {
+            progressive: true,
+            subsampling: JpegSubsampling::default(),
+            optimize_huffman: true,
+            trellis: true,
+        }
+    }
+}
+
+/// Réglages JPEG XL (libjxl).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JxlOptions {
+    // 1 = le plus rapide, 9 = le plus lent / le plus petit
+    pub effort: u8,
+}
+
+impl Default for JxlOptions {
+    fn default() -> Self {
+        // Effort par défaut de libjxl
+        JxlOptions { effort: 7 }
+    }
+}
+
+/// Réglages PNG (quantification imagequant).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PngOptions {
+    // Qualité minimale acceptée par imagequant (défaut : qualité - 20)
+    pub min_quality: Option<u8>,
+}
+
+pub(crate) fn check_range(name: &str, value: u8, min: u8, max: u8) -> Result<()> {
+    if value < min || value > max {
+        return Err(Error::InvalidConfig {
+            details: format!("{} must be between {} and {} (got {})", name, min, max, value),
+        });
+    }
+    Ok(())
+}
+
+impl AvifOptions {
+    pub fn validate(&self) -> Result<()> {
+        check_range("avif.speed", self.speed, 1, 10)?;
+        if let Some(alpha_quality) = self.alpha_quality {
+            check_range("avif.alpha_quality", alpha_quality, 0, 100)?;
+        }
+        Ok(())
+    }
+}
+
+impl WebpOptions {
+    pub fn validate(&self) -> Result<()> {
+        check_range("webp.method", self.method, 0, 6)?;
+        check_range("webp.alpha_quality", self.alpha_quality, 0, 100)?;
+        if let Some(near_lossless) = self.near_lossless {
+            check_range("webp.near_lossless", near_lossless, 0, 100)?;
+        }
+        check_range("webp.passes", self.passes, 1, 10)?;
+        if self.target_size == Some(0) {
+            return Err(Error::InvalidConfig { details: "webp.target_size must be greater than 0".to_string() });
+        }
+        if let Some(psnr) = self.target_psnr {
+            if !(psnr.is_finite() && psnr > 0.0) {
+                return Err(Error::InvalidConfig { details: format!("webp.target_psnr must be positive (got {})", psnr) });
+            }
+        }
+        Ok(())
+    }
+
+    // Une cible libwebp remplace le réglage de qualité
+    pub fn has_target(&self) -> bool {
+        self.target_size.is_some() || self.target_psnr.is_some()
+    }
+}
+
+impl JxlOptions {
+    pub fn validate(&self) -> Result<()> {
+        check_range("jxl.effort", self.effort, 1, 9)
+    }
+}
+
+impl JpegOptions {
+    pub fn validate(&self) -> Result<()> {
+        if self.trellis && !self.progressive {
+            return Err(Error::InvalidConfig { details: "jpeg.trellis requires progressive mode".to_string() });
+        }
+        if !self.optimize_huffman && self.progressive {
+            return Err(Error::InvalidConfig {
+                details: "standard Huffman tables require baseline mode (progressive always optimizes them)".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl PngOptions {
+    pub fn validate(&self, quality: u8) -> Result<()> {
+        if let Some(min_quality) = self.min_quality {
+            check_range("png.min_quality", min_quality, 0, quality)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subsampling_accepts_compact_and_ratio_notations() {
+        for (value, expected) in [
+            ("444", JpegSubsampling::S444),
+            ("4:4:4", JpegSubsampling::S444),
+            ("422", JpegSubsampling::S422),
+            ("4:2:2", JpegSubsampling::S422),
+            ("420", JpegSubsampling::S420),
+            ("4:2:0", JpegSubsampling::S420),
+        ] {
+            assert_eq!(value.parse::<JpegSubsampling>(), Ok(expected), "{}", value);
+        }
+    }
+
+    #[test]
+    fn subsampling_rejects_unknown_values() {
+        for value in ["", "44", "20", "411", "4:1:1", "yuv420"] {
+            let err = value.parse::<JpegSubsampling>().unwrap_err();
+            assert_eq!(err.code(), "invalid_config", "{}", value);
+        }
+    }
+}
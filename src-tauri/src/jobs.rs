@@ -0,0 +1,294 @@
+/*
+ * Copyright (C) 2026  Romain Lathuiliere
+ * * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ */
+
+use rayon::ThreadPool;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Instant;
+
+use crate::batch::{run_batch, BatchEvent, BatchSummary, ProcessResult};
+use crate::control::BatchControl;
+use crate::engine::Options;
+use crate::journal::Journal;
+
+pub type JobId = u64;
+
+/// Un batch soumis à la file : chemins, réglages et pool de workers dédié.
+pub struct Job {
+    pub paths: Vec<String>,
+    pub options: Options,
+    pub pool: ThreadPool,
+    // Progression sauvegardée sur disque pour pouvoir reprendre après un crash
+    pub journal: Option<Journal>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Queued,
+    Running,
+    Paused,
+    Cancelled,
+    Finished,
+}
+
+/// État d'un job tel que renvoyé par `list_jobs`.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub id: JobId,
+    pub state: JobState,
+    pub total: usize,
+    pub processed: usize,
+}
+
+/// Tout ce qui se passe dans un job, toujours accompagné de son `JobId`.
+#[derive(Debug)]
+pub enum JobEvent<'a> {
+    Batch(BatchEvent<'a>),
+    Paused(bool),
+    Finished(&'a BatchSummary),
+    Cancelled(&'a [ProcessResult]),
+}
+
+type Sink = dyn Fn(JobId, JobEvent<'_>) + Send + Sync;
+
+struct Entry {
+    state: JobState,
+    total: usize,
+    processed: usize,
+    output_dir: PathBuf,
+    // Résultats conservés une fois le job terminé, pour l'export de rapport
+    results: Vec<ProcessResult>,
+    control: Arc<BatchControl>,
+    // Pris par le thread qui exécute le job
+    job: Option<Job>,
+}
+
+struct Inner {
+    jobs: BTreeMap<JobId, Entry>,
+    pending: VecDeque<JobId>,
+    running: usize,
+    max_parallel: usize,
+}
+
+/// File de batches : exécutés dans l'ordre de soumission, jusqu'à `max_parallel` à la fois.
+pub struct JobQueue {
+    inner: Mutex<Inner>,
+    next_id: AtomicU64,
+    sink: Box<Sink>,
+}
+
+impl JobQueue {
+    pub fn new<F>(max_parallel: usize, sink: F) -> Arc<Self>
+    where
+        F: Fn(JobId, JobEvent<'_>) + Send + Sync + 'static,
+    {
+        Arc::new(JobQueue {
+            inner: Mutex::new(Inner {
+                jobs: BTreeMap::new(),
+                pending: VecDeque::new(),
+                running: 0,
+                max_parallel: max_parallel.max(1),
+            }),
+            next_id: AtomicU64::new(1),
+            sink: Box::new(sink),
+        })
+    }
+
+    // Un panic sous le verrou (sink, journal...) ne doit pas bloquer toute la file : l'état
+    // reste cohérent, chaque modification étant faite d'un seul tenant
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn submit(self: &Arc<Self>, job: Job) -> JobId {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        {
+            let mut inner = self.lock();
+            inner.jobs.insert(id, Entry {
+                state: JobState::Queued,
+                total: job.paths.len(),
+                processed: 0,
+                output_dir: job.options.output_dir.clone(),
+                results: Vec::new(),
+                control: Arc::new(BatchControl::new()),
+                job: Some(job),
+            });
+            inner.pending.push_back(id);
+        }
+        self.schedule();
+        id
+    }
+
+    pub fn set_max_parallel(self: &Arc<Self>, max_parallel: usize) {
+        self.lock().max_parallel = max_parallel.max(1);
+        self.schedule();
+    }
+
+    pub fn list(&self) -> Vec<JobInfo> {
+        let inner = self.lock();
+        inner.jobs.iter().map(|(id, entry)| JobInfo {
+            id: *id,
+            state: entry.state,
+            total: entry.total,
+            processed: entry.processed,
+        }).collect()
+    }
+
+    /// Résultats d'un job terminé ou annulé, avec son dossier de sortie.
+    pub fn results(&self, id: JobId) -> Option<(Vec<ProcessResult>, PathBuf)> {
+        let inner = self.lock();
+        let entry = inner.jobs.get(&id)?;
+        match entry.state {
+            JobState::Finished | JobState::Cancelled => Some((entry.results.clone(), entry.output_dir.clone())),
+            _ => None,
+        }
+    }
+
+    /// Annule un job : retiré de la file s'il attend encore, arrêté proprement s'il tourne.
+    pub fn cancel(&self, id: JobId) -> bool {
+        let mut inner = self.lock();
+        let Some(entry) = inner.jobs.get_mut(&id) else { return false };
+        match entry.state {
+            JobState::Running | JobState::Paused => {
+                entry.control.cancel();
+                true
+            }
+            JobState::Queued => {
+                entry.state = JobState::Cancelled;
+                if let Some(journal) = entry.job.take().and_then(|job| job.journal) {
+                    journal.remove();
+                }
+                inner.pending.retain(|pending| *pending != id);
+                drop(inner);
+                (self.sink)(id, JobEvent::Cancelled(&[]));
+                true
+            }
+            JobState::Cancelled | JobState::Finished => false,
+        }
+    }
+
+    pub fn pause(&self, id: JobId) -> bool {
+        self.set_paused(id, true)
+    }
+
+    pub fn resume(&self, id: JobId) -> bool {
+        self.set_paused(id, false)
+    }
+
+    fn set_paused(&self, id: JobId, paused: bool) -> bool {
+        let mut inner = self.lock();
+        let Some(entry) = inner.jobs.get_mut(&id) else { return false };
+        match (entry.state, paused) {
+            (JobState::Running, true) => {
+                entry.control.pause();
+                entry.state = JobState::Paused;
+            }
+            (JobState::Paused, false) => {
+                entry.control.resume();
+                entry.state = JobState::Running;
+            }
+            _ => return false,
+        }
+        drop(inner);
+        (self.sink)(id, JobEvent::Paused(paused));
+        true
+    }
+
+    // Démarre les jobs en attente tant qu'il reste des places
+    fn schedule(self: &Arc<Self>) {
+        let mut inner = self.lock();
+        while inner.running < inner.max_parallel {
+            let Some(id) = inner.pending.pop_front() else { break };
+            let Some(entry) = inner.jobs.get_mut(&id) else { continue };
+            let Some(job) = entry.job.take() else { continue };
+            entry.state = JobState::Running;
+            let control = entry.control.clone();
+            inner.running += 1;
+
+            let queue = Arc::clone(self);
+            std::thread::spawn(move || queue.run(id, job, control));
+        }
+    }
+
+    fn run(self: Arc<Self>, id: JobId, job: Job, control: Arc<BatchControl>) {
+        // Libère la place et relance la file même si ce thread panique
+        let _slot = RunningSlot { queue: Arc::clone(&self), id };
+        let started = Instant::now();
+        let results = job.pool.install(|| {
+            run_batch(&job.paths, &job.options, &control, |event| {
+                if let BatchEvent::Processed(result) = event {
+                    if let Some(journal) = &job.journal {
+                        journal.record(result);
+                    }
+                    if let Some(entry) = self.lock().jobs.get_mut(&id) {
+                        entry.processed += 1;
+                    }
+                }
+                (self.sink)(id, JobEvent::Batch(event));
+            })
+        });
+
+        let cancelled = control.is_cancelled();
+        // Terminé ou annulé volontairement : rien à reprendre au prochain lancement
+        if let Some(journal) = &job.journal {
+            journal.remove();
+        }
+        let results: Vec<ProcessResult> = if cancelled {
+            results.into_iter().filter(|r| r.status != "cancelled").collect()
+        } else {
+            results
+        };
+
+        // État mis à jour avant l'événement : le frontend peut exporter le rapport dès réception
+        {
+            let mut inner = self.lock();
+            if let Some(entry) = inner.jobs.get_mut(&id) {
+                entry.state = if cancelled { JobState::Cancelled } else { JobState::Finished };
+                entry.results = results.clone();
+            }
+        }
+
+        if cancelled {
+            (self.sink)(id, JobEvent::Cancelled(&results));
+        } else {
+            let summary = BatchSummary::new(&results, started.elapsed());
+            (self.sink)(id, JobEvent::Finished(&summary));
+        }
+    }
+}
+
+// Place occupée par un job en cours dans `Inner::running`
+struct RunningSlot {
+    queue: Arc<JobQueue>,
+    id: JobId,
+}
+
+impl Drop for RunningSlot {
+    fn drop(&mut self) {
+        let interrupted = {
+            let mut inner = self.queue.lock();
+            inner.running -= 1;
+            match inner.jobs.get_mut(&self.id) {
+                // Thread interrompu avant la fin : le job ne doit pas rester "running"
+                Some(entry) if matches!(entry.state, JobState::Running | JobState::Paused) => {
+                    entry.state = JobState::Cancelled;
+                    true
+                }
+                _ => false,
+            }
+        };
+        if interrupted {
+            (self.queue.sink)(self.id, JobEvent::Cancelled(&[]));
+        }
+        self.queue.schedule();
+    }
+}
@@ -0,0 +1,213 @@
+/*
+ * Copyright (C) 2026  Romain Lathuiliere
+ * * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ */
+
+// Journal de progression d'un batch, sur disque, pour reprendre après un crash.
+//
+// Format JSON Lines, en ajout seul (une ligne coupée par un crash est simplement ignorée) :
+//   ligne 1      -> JournalHeader (config complète du batch)
+//   lignes 2..n  -> JournalEntry (une image terminée et sa sortie éventuelle)
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::batch::ProcessResult;
+use crate::engine::Options;
+use crate::error::{Error, Result};
+
+const EXTENSION: &str = "journal";
+
+/// Config d'origine du batch, suffisante pour le relancer à l'identique.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalHeader {
+    pub paths: Vec<String>,
+    pub options: Options,
+    pub threads: Option<usize>,
+    pub background: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub path: String,
+    pub output: Option<String>,
+}
+
+/// Batch interrompu retrouvé au démarrage.
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingBatch {
+    pub id: String,
+    pub header: JournalHeader,
+    pub completed: Vec<JournalEntry>,
+}
+
+impl PendingBatch {
+    /// Chemins pas encore traités, dans l'ordre d'origine.
+    pub fn remaining(&self) -> Vec<String> {
+        let done: HashSet<&str> = self.completed.iter().map(|e| e.path.as_str()).collect();
+        self.header.paths.iter().filter(|p| !done.contains(p.as_str())).cloned().collect()
+    }
+}
+
+/// Journal ouvert en écriture pour un batch en cours.
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl Journal {
+    pub fn create(dir: &Path, header: &JournalHeader) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or_default();
+        let path = dir.join(format!("{}.{}", nanos, EXTENSION));
+
+        let mut file = File::create(&path)?;
+        let line = serde_json::to_string(header)
+            .map_err(|e| Error::Internal { details: format!("journal serialization: {}", e) })?;
+        writeln!(file, "{}", line)?;
+        file.sync_data()?;
+
+        Ok(Journal { path, file: Mutex::new(file) })
+    }
+
+    /// Rouvre le journal d'un batch interrompu pour y poursuivre l'écriture.
+    pub fn reopen(dir: &Path, id: &str) -> Result<Self> {
+        let path =
+            journal_path(dir, id).ok_or_else(|| Error::NotFound { details: format!("invalid journal id: {}", id) })?;
+        let file = OpenOptions::new().append(true).open(&path)?;
+        Ok(Journal { path, file: Mutex::new(file) })
+    }
+
+    /// Note une image traitée (succès ou ignorée). Les erreurs et les annulations ne sont pas
+    /// notées : la reprise retente ces images.
+    pub fn record(&self, result: &ProcessResult) {
+        if !result.is_success() && !result.status.starts_with("skipped") {
+            return;
+        }
+        let entry = JournalEntry {
+            path: result.original.clone(),
+            output: result.new_path.clone(),
+        };
+        if let Ok(line) = serde_json::to_string(&entry) {
+            let mut file = self.file.lock().unwrap();
+            // Best effort : un journal incomplet fait juste retraiter quelques images
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+
+    /// Batch terminé ou annulé volontairement : plus rien à reprendre.
+    pub fn remove(&self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+// Les ids viennent du frontend : uniquement des chiffres, pas de "../"
+fn journal_path(dir: &Path, id: &str) -> Option<PathBuf> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(dir.join(format!("{}.{}", id, EXTENSION)))
+}
+
+fn read_journal(path: &Path) -> Option<PendingBatch> {
+    let id = path.file_stem()?.to_string_lossy().to_string();
+    let mut lines = BufReader::new(File::open(path).ok()?).lines();
+
+    let header: JournalHeader = serde_json::from_str(&lines.next()?.ok()?).ok()?;
+    let completed = lines
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<JournalEntry>(&line).ok())
+        .collect();
+
+    Some(PendingBatch { id, header, completed })
+}
+
+/// Liste les batches interrompus (crash, fermeture de l'app) présents dans `dir`.
+pub fn list_pending(dir: &Path) -> Vec<PendingBatch> {
+    let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+    let mut pending: Vec<PendingBatch> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == EXTENSION))
+        .filter_map(|p| read_journal(&p))
+        .collect();
+    pending.sort_by(|a, b| a.id.cmp(&b.id));
+    pending
+}
+
+pub fn load_pending(dir: &Path, id: &str) -> Option<PendingBatch> {
+    read_journal(&journal_path(dir, id)?)
+}
+
+pub fn discard_pending(dir: &Path, id: &str) {
+    if let Some(path) = journal_path(dir, id) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = concat!(
+        r#"{"paths":["a.jpg","b.jpg","c.jpg"],"#,
+        r#""options":{"output_dir":"out","format":"webp","quality":80},"threads":null,"background":false}"#
+    );
+
+    fn write_journal(name: &str, content: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rimages-journal-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("1.{}", EXTENSION));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn entry(path: &str) -> JournalEntry {
+        JournalEntry { path: path.to_string(), output: None }
+    }
+
+    #[test]
+    fn remaining_keeps_the_original_order() {
+        let header: JournalHeader = serde_json::from_str(HEADER).unwrap();
+        let batch = PendingBatch { id: "1".to_string(), header, completed: vec![entry("b.jpg")] };
+        assert_eq!(batch.remaining(), ["a.jpg", "c.jpg"]);
+    }
+
+    #[test]
+    fn remaining_is_empty_once_everything_is_recorded() {
+        let header: JournalHeader = serde_json::from_str(HEADER).unwrap();
+        let completed = vec![entry("c.jpg"), entry("a.jpg"), entry("b.jpg")];
+        let batch = PendingBatch { id: "1".to_string(), header, completed };
+        assert!(batch.remaining().is_empty());
+    }
+
+    #[test]
+    fn truncated_last_line_is_ignored() {
+        let content = format!("{}\n{}\n{}", HEADER, r#"{"path":"a.jpg","output":"out/a.webp"}"#, r#"{"path":"b.j"#);
+        let path = write_journal("truncated", &content);
+
+        let batch = read_journal(&path).unwrap();
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+        assert_eq!(batch.id, "1");
+        assert_eq!(batch.completed.len(), 1);
+        assert_eq!(batch.remaining(), ["b.jpg", "c.jpg"]);
+    }
+
+    #[test]
+    fn truncated_header_discards_the_journal() {
+        let path = write_journal("header", r#"{"paths":["a.jpg"],"opt"#);
+
+        let batch = read_journal(&path);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+        assert!(batch.is_none());
+    }
+}
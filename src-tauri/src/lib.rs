@@ -1,15 +1,50 @@
-// Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
-#[tauri::command]
-fn greet(name: &str) -> String {
-    format!("Hello, {}! You've been greeted from Rust!", name)
-}
+/*
+ * Copyright (C) 2026  Romain Lathuiliere
+ * * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ */
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_dialog::init())
-        .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![greet])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
-}
+// Moteur de compression "headless" : aucune dépendance à AppHandle ni aux événements.
+// Les commandes Tauri de main.rs ne sont que des wrappers autour de cette API.
+
+mod animation;
+mod auto;
+mod batch;
+mod control;
+mod decode;
+mod encoders;
+mod engine;
+mod error;
+mod format;
+mod jobs;
+mod journal;
+mod metrics;
+mod pool;
+mod report;
+mod search;
+
+pub use batch::{run_batch, BatchEvent, BatchSummary, FailedFile, ProcessResult, SlowFile};
+pub use control::BatchControl;
+pub use decode::{open_image, read_dimensions};
+pub use encoders::{
+    encode_avif, encode_avif_lossless, encode_jpeg, encode_jxl, encode_jxl_from_jpeg, encode_jxl_lossless, encode_png,
+    encode_png_lossless, encode_webp, encode_webp_animation, encode_webp_lossless,
+};
+pub use engine::{
+    compress_file, compress_file_with_control, encode_image, estimate_file, get_unique_path, output_path_for,
+    resize_to_fit, target_dimensions, Estimate, IfLarger, Options, Outcome, SkipReason,
+};
+pub use error::{Error, Result};
+pub use format::{
+    AvifAlphaMode, AvifBitDepth, AvifColorModel, AvifOptions, JpegOptions, JpegSubsampling, JxlOptions, OutputFormat,
+    PngOptions, WebpOptions,
+};
+pub use jobs::{Job, JobEvent, JobId, JobInfo, JobQueue, JobState};
+pub use journal::{
+    discard_pending, list_pending, load_pending, Journal, JournalEntry, JournalHeader, PendingBatch,
+};
+pub use metrics::{dssim, ssim};
+pub use pool::{build_pool, default_threads};
+pub use report::{write_report, ReportFormat};
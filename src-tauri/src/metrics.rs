@@ -0,0 +1,124 @@
+/*
+ * Copyright (C) 2026  Romain Lathuiliere
+ * * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ */
+
+// Métrique perceptuelle (SSIM) entre la source redimensionnée et sa version encodée.
+
+use image::{DynamicImage, GrayImage, Luma};
+
+// Fenêtres 8x8 sans recouvrement : assez précis pour piloter la qualité, bien plus rapide
+// qu'un noyau gaussien 11x11
+const WINDOW: u32 = 8;
+const C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+const C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+
+/// SSIM moyen sur la luminance, entre 0 (aucun rapport) et 1 (identique).
+pub fn ssim(reference: &DynamicImage, candidate: &DynamicImage) -> f64 {
+    if (reference.width(), reference.height()) != (candidate.width(), candidate.height()) {
+        return 0.0;
+    }
+    if !reference.color().has_alpha() && !candidate.color().has_alpha() {
+        return luma_ssim(&reference.to_luma8(), &candidate.to_luma8());
+    }
+    // Transparence : images composées sur noir puis sur blanc. Un écart d'alpha se voit sur
+    // l'un des deux fonds, le RGB sous un pixel transparent ne compte plus
+    [0, 255]
+        .into_iter()
+        .map(|background| luma_ssim(&luma_over(reference, background), &luma_over(candidate, background)))
+        .fold(1.0, f64::min)
+}
+
+// Luminance (mêmes coefficients que `to_luma8`) du pixel composé sur un fond gris uni
+fn luma_over(img: &DynamicImage, background: u8) -> GrayImage {
+    let rgba = img.to_rgba8();
+    GrayImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let luma = 0.2126 * r as f64 + 0.7152 * g as f64 + 0.0722 * b as f64;
+        let alpha = a as f64 / 255.0;
+        Luma([(luma * alpha + background as f64 * (1.0 - alpha)).round() as u8])
+    })
+}
+
+fn luma_ssim(a: &GrayImage, b: &GrayImage) -> f64 {
+    let (width, height) = a.dimensions();
+    // Image plus petite qu'une fenêtre : une seule fenêtre couvrant tout
+    let window_w = WINDOW.min(width).max(1);
+    let window_h = WINDOW.min(height).max(1);
+
+    let mut total = 0.0;
+    let mut count = 0u64;
+    let mut y = 0;
+    while y + window_h <= height {
+        let mut x = 0;
+        while x + window_w <= width {
+            total += window_ssim(a, b, x, y, window_w, window_h);
+            count += 1;
+            x += window_w;
+        }
+        y += window_h;
+    }
+
+    if count == 0 {
+        1.0
+    } else {
+        total / count as f64
+    }
+}
+
+/// DSSIM (dissimilarité) : 0 = identique, plus c'est grand plus ça se voit.
+pub fn dssim(reference: &DynamicImage, candidate: &DynamicImage) -> f64 {
+    let s = ssim(reference, candidate).max(f64::EPSILON);
+    1.0 / s - 1.0
+}
+
+fn window_ssim(a: &GrayImage, b: &GrayImage, x0: u32, y0: u32, w: u32, h: u32) -> f64 {
+    let n = (w * h) as f64;
+    let (mut sum_a, mut sum_b) = (0.0, 0.0);
+    let (mut sum_aa, mut sum_bb, mut sum_ab) = (0.0, 0.0, 0.0);
+
+    for y in y0..y0 + h {
+        for x in x0..x0 + w {
+            let pa = a.get_pixel(x, y)[0] as f64;
+            let pb = b.get_pixel(x, y)[0] as f64;
+            sum_a += pa;
+            sum_b += pb;
+            sum_aa += pa * pa;
+            sum_bb += pb * pb;
+            sum_ab += pa * pb;
+        }
+    }
+
+    let mean_a = sum_a / n;
+    let mean_b = sum_b / n;
+    let var_a = sum_aa / n - mean_a * mean_a;
+    let var_b = sum_bb / n - mean_b * mean_b;
+    let covar = sum_ab / n - mean_a * mean_b;
+
+    ((2.0 * mean_a * mean_b + C1) * (2.0 * covar + C2))
+        / ((mean_a * mean_a + mean_b * mean_b + C1) * (var_a + var_b + C2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    fn uniform(pixel: [u8; 4]) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(16, 16, Rgba(pixel)))
+    }
+
+    #[test]
+    fn alpha_changes_lower_the_score() {
+        // Même RGB, l'une opaque et l'autre transparente : to_luma8 ne verrait aucune différence
+        assert!(ssim(&uniform([200, 50, 50, 255]), &uniform([200, 50, 50, 0])) < 0.5);
+    }
+
+    #[test]
+    fn rgb_under_transparent_pixels_is_ignored() {
+        assert_eq!(ssim(&uniform([200, 50, 50, 0]), &uniform([0, 0, 0, 0])), 1.0);
+    }
+}
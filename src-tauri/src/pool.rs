@@ -0,0 +1,38 @@
+/*
+ * Copyright (C) 2026  Romain Lathuiliere
+ * * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ */
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use thread_priority::{set_current_thread_priority, ThreadPriority};
+
+use crate::error::{Error, Result};
+
+/// Nombre de workers par défaut : tous les cœurs disponibles.
+pub fn default_threads() -> usize {
+    std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4)
+}
+
+/// Crée le pool rayon d'un batch.
+///
+/// En mode `background`, les workers tournent en priorité OS minimale et sont
+/// plafonnés à la moitié des cœurs, pour que la machine reste utilisable.
+pub fn build_pool(threads: Option<usize>, background: bool) -> Result<ThreadPool> {
+    let mut num_threads = threads.unwrap_or_else(default_threads).max(1);
+    if background {
+        num_threads = num_threads.min((default_threads() / 2).max(1));
+    }
+
+    let mut builder = ThreadPoolBuilder::new().num_threads(num_threads);
+    if background {
+        builder = builder.start_handler(|_| {
+            // Best effort : certains OS refusent sans privilèges, on continue quand même
+            let _ = set_current_thread_priority(ThreadPriority::Min);
+        });
+    }
+
+    builder.build().map_err(|e| Error::Io { details: e.to_string() })
+}
@@ -0,0 +1,128 @@
+/*
+ * Copyright (C) 2026  Romain Lathuiliere
+ * * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ */
+
+// Export du rapport d'un batch (une ligne par image) en CSV ou JSON.
+
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::batch::ProcessResult;
+use crate::engine::get_unique_path;
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Csv,
+    Json,
+}
+
+impl ReportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Csv => "csv",
+            ReportFormat::Json => "json",
+        }
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(ReportFormat::Csv),
+            "json" => Ok(ReportFormat::Json),
+            other => Err(Error::InvalidConfig { details: format!("unknown report format: {}", other) }),
+        }
+    }
+}
+
+const CSV_HEADER: &str = "path,status,output,original_size,output_size,savings_percent,\
+input_width,input_height,output_width,output_height,format,quality,format_reason,warning,encode_ms,rejected_size,error_code,error";
+
+// Échappement CSV minimal (RFC 4180) : guillemets si virgule, guillemet ou retour à la ligne
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn opt<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn to_csv(results: &[ProcessResult]) -> String {
+    let mut out = String::from(CSV_HEADER);
+    out.push('\n');
+    for r in results {
+        let row = [
+            csv_field(&r.original),
+            csv_field(&r.status),
+            csv_field(&opt(&r.new_path)),
+            opt(&r.original_size),
+            opt(&r.output_size),
+            opt(&r.savings_percent),
+            opt(&r.input_width),
+            opt(&r.input_height),
+            opt(&r.output_width),
+            opt(&r.output_height),
+            csv_field(&opt(&r.format)),
+            opt(&r.quality),
+            csv_field(&opt(&r.format_reason)),
+            csv_field(&opt(&r.warning)),
+            opt(&r.encode_ms),
+            opt(&r.rejected_size),
+            r.error.as_ref().map(|e| e.code().to_string()).unwrap_or_default(),
+            csv_field(&opt(&r.error_msg)),
+        ];
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Écrit le rapport dans `dir` (à côté des sorties) et renvoie son chemin.
+pub fn write_report(results: &[ProcessResult], dir: &Path, format: ReportFormat) -> Result<PathBuf> {
+    let data = match format {
+        ReportFormat::Csv => to_csv(results).into_bytes(),
+        ReportFormat::Json => serde_json::to_vec_pretty(results)
+            .map_err(|e| Error::Internal { details: format!("report serialization: {}", e) })?,
+    };
+
+    let path = get_unique_path(dir.join(format!("rimages-report.{}", format.extension())));
+    fs::write(&path, data)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_values_are_left_as_is() {
+        assert_eq!(csv_field("photos/a.jpg"), "photos/a.jpg");
+        assert_eq!(csv_field(""), "");
+    }
+
+    #[test]
+    fn separators_and_line_breaks_are_quoted() {
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(csv_field("cr\rlf"), "\"cr\rlf\"");
+    }
+
+    #[test]
+    fn quotes_are_doubled() {
+        assert_eq!(csv_field(r#"say "hi""#), r#""say ""hi""""#);
+    }
+}
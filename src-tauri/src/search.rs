@@ -0,0 +1,207 @@
+/*
+ * Copyright (C) 2026  Romain Lathuiliere
+ * * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ */
+
+// Recherche automatique de la qualité : mode "taille cible" et mode "qualité perceptuelle".
+
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageError};
+
+use crate::engine::{encode_image, Options};
+use crate::error::{Error, Result};
+use crate::format::OutputFormat;
+use crate::metrics::ssim;
+
+// En dessous, les artefacts deviennent inacceptables : on réduit plutôt les dimensions
+const MIN_SEARCH_QUALITY: u8 = 10;
+// Chaque étape réduit largeur et hauteur de 15 %
+const DOWNSCALE_FACTOR: f64 = 0.85;
+const MAX_DOWNSCALE_STEPS: u32 = 8;
+
+/// Encodage final retenu par une recherche.
+pub(crate) struct Encoded {
+    pub image: DynamicImage,
+    pub data: Vec<u8>,
+    pub quality: u8,
+    pub format: OutputFormat,
+    // Justification du choix, uniquement en format automatique
+    pub reason: Option<String>,
+}
+
+impl Encoded {
+    pub fn new(image: DynamicImage, data: Vec<u8>, options: &Options) -> Self {
+        Encoded { image, data, quality: options.quality, format: options.format, reason: None }
+    }
+}
+
+/// Plus haute qualité (plafonnée à `options.quality`) dont la sortie tient dans `budget` octets.
+/// Si même la qualité minimale dépasse, on réduit les dimensions et on recommence.
+pub(crate) fn fit_to_size(img: DynamicImage, options: &Options, budget: u64) -> Result<Encoded> {
+    // Chaque réduction repart de l'original : un seul rééchantillonnage, sans flou cumulé
+    let mut downscaled: Option<DynamicImage> = None;
+    for step in 1..=MAX_DOWNSCALE_STEPS + 1 {
+        let current = downscaled.as_ref().unwrap_or(&img);
+        if let Some((data, quality)) = best_quality_under(current, options, budget)? {
+            let image = downscaled.unwrap_or(img);
+            return Ok(Encoded { image, data, quality, format: options.format, reason: None });
+        }
+
+        let scale = DOWNSCALE_FACTOR.powi(step as i32);
+        let width = ((img.width() as f64 * scale) as u32).max(1);
+        let height = ((img.height() as f64 * scale) as u32).max(1);
+        if (width, height) == (current.width(), current.height()) {
+            break;
+        }
+        downscaled = Some(img.resize_exact(width, height, FilterType::Lanczos3));
+    }
+
+    Err(Error::TargetNotReached {
+        details: format!("could not fit under {} bytes", budget),
+    })
+}
+
+/// Plus haute qualité (plafonnée à `options.quality`) dont la sortie tient dans `budget`,
+/// sans toucher aux dimensions. Recherche dichotomique : la taille croît avec la qualité.
+pub(crate) fn best_quality_under(img: &DynamicImage, options: &Options, budget: u64) -> Result<Option<(Vec<u8>, u8)>> {
+    let mut lo = MIN_SEARCH_QUALITY.min(options.quality);
+    let mut hi = options.quality;
+    let mut best = None;
+    // Un échec d'encodage à une qualité sondée (ex : QualityTooLow d'imagequant) compte comme
+    // "ne tient pas" : une qualité plus basse peut encore passer
+    let mut failure = None;
+    let mut encoded = false;
+
+    while lo <= hi {
+        let quality = lo + (hi - lo) / 2;
+        match encode_image(img, &options.with_quality(quality)) {
+            Ok(data) if data.len() as u64 <= budget => {
+                best = Some((data, quality));
+                lo = quality + 1;
+                continue;
+            }
+            Ok(_) => encoded = true,
+            Err(e) => failure = Some(e),
+        }
+        if quality == 0 {
+            break;
+        }
+        hi = quality - 1;
+    }
+
+    // Aucune qualité n'a pu être encodée : vraie erreur, pas un budget trop serré
+    match (best, failure) {
+        (None, Some(e)) if !encoded => Err(e),
+        (best, _) => Ok(best),
+    }
+}
+
+/// Plus basse qualité dont le SSIM (luminance, contre l'image redimensionnée) reste
+/// au-dessus de `min_ssim`. Si aucune n'y parvient, on garde la qualité plafond :
+/// le booléen indique si le seuil a été atteint.
+pub(crate) fn fit_to_ssim(img: DynamicImage, options: &Options, min_ssim: f64) -> Result<(Encoded, bool)> {
+    let mut lo = MIN_SEARCH_QUALITY.min(options.quality);
+    let mut hi = options.quality;
+    let mut best: Option<(Vec<u8>, u8)> = None;
+
+    // Recherche dichotomique : le SSIM croît avec la qualité
+    while lo <= hi {
+        let quality = lo + (hi - lo) / 2;
+        let go_lower = match encode_image(&img, &options.with_quality(quality)) {
+            Ok(data) => {
+                let passed = encoded_ssim(&img, &data, options.format)? >= min_ssim;
+                if passed {
+                    best = Some((data, quality));
+                }
+                passed
+            }
+            // Échec d'encodage à cette qualité : on continue plus bas, comme en taille cible
+            Err(_) => true,
+        };
+        if !go_lower {
+            lo = quality + 1;
+        } else if quality == 0 {
+            break;
+        } else {
+            hi = quality - 1;
+        }
+    }
+
+    let met = best.is_some();
+    let (data, quality) = match best {
+        Some(found) => found,
+        None => (encode_image(&img, options)?, options.quality),
+    };
+    Ok((Encoded { image: img, data, quality, format: options.format, reason: None }, met))
+}
+
+/// SSIM entre `img` et sa version encodée `data`, relue avec le décodeur de `format`.
+pub(crate) fn encoded_ssim(img: &DynamicImage, data: &[u8], format: OutputFormat) -> Result<f64> {
+    let image_format = format.image_format().ok_or_else(|| Error::UnsupportedFormat {
+        details: format!("cannot decode {} output", format),
+    })?;
+    let decoded = image::load_from_memory_with_format(data, image_format).map_err(|e| match e {
+        // Décodeur non compilé (AVIF sans dav1d) : ce n'est pas un échec d'encodage
+        ImageError::Unsupported(_) => Error::decode(e),
+        e => Error::encode(format.as_str(), format!("decode for SSIM: {}", e)),
+    })?;
+    Ok(ssim(img, &decoded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbImage;
+
+    fn gradient(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            image::Rgb([(x * 255 / width) as u8, (y * 255 / height) as u8, ((x + y) % 256) as u8])
+        }))
+    }
+
+    fn jpeg_options(quality: u8) -> Options {
+        serde_json::from_value(serde_json::json!({ "output_dir": ".", "format": "jpg", "quality": quality })).unwrap()
+    }
+
+    #[test]
+    fn best_quality_under_keeps_the_ceiling_when_everything_fits() {
+        let (_, quality) = best_quality_under(&gradient(64, 64), &jpeg_options(80), u64::MAX).unwrap().unwrap();
+        assert_eq!(quality, 80);
+    }
+
+    #[test]
+    fn best_quality_under_returns_none_when_nothing_fits() {
+        assert!(best_quality_under(&gradient(64, 64), &jpeg_options(80), 1).unwrap().is_none());
+    }
+
+    #[test]
+    fn best_quality_under_stays_within_budget_and_bounds() {
+        let img = gradient(128, 128);
+        let options = jpeg_options(90);
+        let budget = encode_image(&img, &options.with_quality(50)).unwrap().len() as u64;
+
+        let (data, quality) = best_quality_under(&img, &options, budget).unwrap().unwrap();
+        assert!(data.len() as u64 <= budget);
+        assert!((MIN_SEARCH_QUALITY..=90).contains(&quality));
+    }
+
+    #[test]
+    fn fit_to_size_downscales_when_the_minimum_quality_is_too_heavy() {
+        let img = gradient(256, 256);
+        let options = jpeg_options(80);
+        let budget = encode_image(&img, &options.with_quality(MIN_SEARCH_QUALITY)).unwrap().len() as u64 / 2;
+
+        let encoded = fit_to_size(img, &options, budget).unwrap();
+        assert!(encoded.data.len() as u64 <= budget);
+        assert!(encoded.image.width() < 256 && encoded.image.height() < 256);
+    }
+
+    #[test]
+    fn fit_to_size_gives_up_on_an_impossible_budget() {
+        let err = fit_to_size(gradient(64, 64), &jpeg_options(80), 1).err().unwrap();
+        assert_eq!(err.code(), "target_not_reached");
+    }
+}
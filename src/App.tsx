@@ -23,11 +23,40 @@ import {
   Image as ImageIcon,
   Check,
   PartyPopper,
+  Pause,
+  Play,
 } from "lucide-react";
 import "./App.css";
 import { downloadDir } from "@tauri-apps/api/path";
 
+// Entrées acceptées : formats `image`, HEIC/HEIF et RAW d'appareil photo
+const SUPPORTED_IMAGE =
+  /\.(jpg|jpeg|png|gif|webp|avif|heic|heif|dng|cr2|cr3|nef|nrw|arw|raf|orf|rw2|pef)$/i;
+
 // --- TYPES ---
+interface AvifSettings {
+  speed: number;
+  alpha_quality: number | null;
+  bit_depth: "8" | "10" | null;
+  color_model: "ycbcr" | "rgb";
+  alpha_mode: "clean" | "dirty" | "premultiplied";
+}
+
+interface WebpSettings {
+  method: number;
+  alpha_quality: number;
+  near_lossless: number | null;
+  exact: boolean;
+  sharp_yuv: boolean;
+}
+
+interface JpegSettings {
+  progressive: boolean;
+  subsampling: "444" | "422" | "420";
+  optimize_huffman: boolean;
+  trellis: boolean;
+}
+
 interface CompressConfig {
   paths: string[];
   output_dir: string;
@@ -35,16 +64,57 @@ interface CompressConfig {
   quality: number;
   max_width: number | null;
   max_height: number | null;
+  lossless?: boolean;
+  target_size?: number | null;
+  min_ssim?: number | null;
+  threads?: number | null;
+  background?: boolean;
+  if_larger?: "keep" | "copy" | "skip" | "retry";
+  min_savings_percent?: number | null;
+  min_savings_bytes?: number | null;
+  copy_skipped?: boolean;
+  avif?: AvifSettings;
+  webp?: WebpSettings;
+  jpeg?: JpegSettings;
+  jxl?: { effort: number };
   prefix: string | null;
   suffix: string | null;
   custom_names: Record<string, string> | null;
 }
 
 interface ProcessResult {
+  job_id: number;
   original: string;
-  status: "success" | "error";
+  // "skipped-larger" : sortie plus lourde que la source, écartée
+  status: "success" | "error" | "cancelled" | `skipped-${string}`;
   error_msg: string | null;
+  // Code stable : decode, unsupported_format, encode, io, permission_denied, out_of_space, not_found, internal...
+  error: { code: string; details?: string; codec?: string } | null;
   new_path: string | null;
+  original_size: number | null;
+  output_size: number | null;
+  savings_percent: number | null;
+  input_width: number | null;
+  input_height: number | null;
+  output_width: number | null;
+  output_height: number | null;
+  format: string | null;
+  quality: number | null;
+  // Format automatique : pourquoi ce format a été retenu
+  format_reason: string | null;
+  // Perte signalée, ex : animation aplatie en image fixe
+  warning: string | null;
+  encode_ms: number | null;
+  rejected_size: number | null;
+}
+
+interface PendingBatch {
+  id: string;
+  header: {
+    paths: string[];
+    options: { output_dir: string; format: string; quality: number };
+  };
+  completed: { path: string; output: string | null }[];
 }
 
 interface PreviewResult {
@@ -63,7 +133,7 @@ interface FileItem {
   height?: number;
 }
 
-type FileStatus = "idle" | "processing" | "success" | "error";
+type FileStatus = "idle" | "processing" | "success" | "error" | "skipped";
 
 // Helper formatting
 const formatBytes = (bytes?: number) => {
@@ -211,6 +281,7 @@ const FileItemRow = memo(
           )}
           {status === "success" && <CheckCircle size={18} color="#10b981" />}
           {status === "error" && <AlertCircle size={18} color="#ef4444" />}
+          {status === "skipped" && <CheckCircle size={18} color="#94a3b8" />}
 
           {status === "idle" && (
             <button
@@ -248,14 +319,46 @@ function App() {
   const [statusMap, setStatusMap] = useState<Record<string, FileStatus>>({});
   const [processedCount, setProcessedCount] = useState(0);
   const [isSuccess, setIsSuccess] = useState(false);
+  const [isPaused, setIsPaused] = useState(false);
 
   const unlisteners = useRef<UnlistenFn[]>([]);
+  const jobId = useRef<number | null>(null);
 
   // Settings
   const [format, setFormat] = useState("webp");
   const [quality, setQuality] = useState(85);
   const [maxWidth, setMaxWidth] = useState<string>("");
   const [maxHeight, setMaxHeight] = useState<string>("");
+  const [background, setBackground] = useState(false);
+  const [lossless, setLossless] = useState(false);
+  const [targetSizeKb, setTargetSizeKb] = useState<string>("");
+  const [minSsim, setMinSsim] = useState<string>("");
+  const [ifLarger, setIfLarger] =
+    useState<NonNullable<CompressConfig["if_larger"]>>("keep");
+  const [minSavingsPercent, setMinSavingsPercent] = useState<string>("");
+  const [minSavingsKb, setMinSavingsKb] = useState<string>("");
+  const [copySkipped, setCopySkipped] = useState(false);
+  const [avif, setAvif] = useState<AvifSettings>({
+    speed: 4,
+    alpha_quality: null,
+    bit_depth: null,
+    color_model: "ycbcr",
+    alpha_mode: "clean",
+  });
+  const [webp, setWebp] = useState<WebpSettings>({
+    method: 4,
+    alpha_quality: 100,
+    near_lossless: null,
+    exact: false,
+    sharp_yuv: false,
+  });
+  const [jpeg, setJpeg] = useState<JpegSettings>({
+    progressive: true,
+    subsampling: "420",
+    optimize_huffman: true,
+    trellis: true,
+  });
+  const [jxlEffort, setJxlEffort] = useState(7);
 
   const cleanupListeners = () => {
     unlisteners.current.forEach((f) => f());
@@ -280,6 +383,39 @@ function App() {
     };
   }, []);
 
+  // Reprise d'un batch interrompu (crash / fermeture) au lancement
+  useEffect(() => {
+    const checkInterrupted = async () => {
+      try {
+        const pending = await invoke<PendingBatch[]>(
+          "list_interrupted_batches",
+        );
+        for (const batch of pending) {
+          const done = new Set(batch.completed.map((c) => c.path));
+          const remaining = batch.header.paths.filter((p) => !done.has(p));
+          const ok = window.confirm(
+            `A previous batch was interrupted (${remaining.length} of ${batch.header.paths.length} images left). Resume it?`,
+          );
+          if (!ok) {
+            await invoke("discard_interrupted_batch", { journalId: batch.id });
+            continue;
+          }
+          await addFiles(remaining);
+          setOutputDir(batch.header.options.output_dir);
+          await runJob(() =>
+            invoke<number>("resume_interrupted_batch", {
+              journalId: batch.id,
+            }),
+          );
+          break;
+        }
+      } catch (err) {
+        console.error("Error checking interrupted batches:", err);
+      }
+    };
+    checkInterrupted();
+  }, []);
+
   useEffect(() => {
     const initDefaultDir = async () => {
       try {
@@ -300,14 +436,30 @@ function App() {
       runSimulation();
     }, 600);
     return () => clearTimeout(timer);
-  }, [quality, format, maxWidth, maxHeight, files.length]);
+  }, [
+    quality,
+    format,
+    maxWidth,
+    maxHeight,
+    lossless,
+    targetSizeKb,
+    minSsim,
+    ifLarger,
+    minSavingsPercent,
+    minSavingsKb,
+    avif,
+    webp,
+    jpeg,
+    jxlEffort,
+    files.length,
+  ]);
 
   const addFiles = async (newPaths: string[]) => {
     setIsSuccess(false);
 
     const currentPaths = new Set(files.map((f) => f.path));
     const uniquePaths = newPaths.filter(
-      (p) => !currentPaths.has(p) && /\.(jpg|jpeg|png|webp|avif)$/i.test(p),
+      (p) => !currentPaths.has(p) && SUPPORTED_IMAGE.test(p),
     );
 
     if (uniquePaths.length === 0) return;
@@ -369,7 +521,32 @@ function App() {
   const selectFiles = async () => {
     const selected = await open({
       multiple: true,
-      filters: [{ name: "Images", extensions: ["png", "jpg", "jpeg", "webp"] }],
+      filters: [
+        {
+          name: "Images",
+          extensions: [
+            "png",
+            "jpg",
+            "jpeg",
+            "gif",
+            "webp",
+            "avif",
+            "heic",
+            "heif",
+            // RAW d'appareil photo
+            "dng",
+            "cr2",
+            "cr3",
+            "nef",
+            "nrw",
+            "arw",
+            "raf",
+            "orf",
+            "rw2",
+            "pef",
+          ],
+        },
+      ],
     });
     if (selected && Array.isArray(selected)) addFiles(selected as string[]);
   };
@@ -390,9 +567,23 @@ function App() {
       quality: Number(quality),
       max_width: maxWidth ? Number(maxWidth) : null,
       max_height: maxHeight ? Number(maxHeight) : null,
+      lossless: lossless && format !== "jpg",
+      target_size:
+        targetSizeKb && !lossless ? Math.round(Number(targetSizeKb) * 1024) : null,
+      min_ssim:
+        minSsim && !targetSizeKb && !lossless && format !== "jxl"
+          ? Number(minSsim)
+          : null,
       prefix: null,
       suffix: null,
       custom_names: null,
+      if_larger: ifLarger,
+      min_savings_percent: minSavingsPercent ? Number(minSavingsPercent) : null,
+      min_savings_bytes: minSavingsKb ? Math.round(Number(minSavingsKb) * 1024) : null,
+      avif,
+      webp,
+      jpeg,
+      jxl: { effort: jxlEffort },
     };
 
     const unlisten = await listen<PreviewResult[]>("preview-done", (event) => {
@@ -420,58 +611,148 @@ function App() {
     }
   };
 
-  const startCompression = async () => {
-    if (files.length === 0 || !outputDir) return;
+  // Branche les listeners du batch puis le soumet (nouveau batch ou reprise)
+  const runJob = async (submit: () => Promise<number>) => {
     cleanupListeners();
     setIsProcessing(true);
     setIsSuccess(false);
+    setIsPaused(false);
     setProcessedCount(0);
     setStatusMap({});
 
-    const config: CompressConfig = {
-      paths: files.map((f) => f.path),
-      output_dir: outputDir,
-      format,
-      quality: Number(quality),
-      max_width: maxWidth ? Number(maxWidth) : null,
-      max_height: maxHeight ? Number(maxHeight) : null,
-      prefix: null,
-      suffix: null,
-      custom_names: null,
-    };
+    // Les événements d'autres batchs sont ignorés. Ceux reçus avant que `submit` ne
+    // renvoie l'id du batch sont mis de côté, puis rejoués s'ils le concernent
+    jobId.current = null;
+    let pending: { job_id: number; run: () => void }[] | null = [];
+    const forJob =
+      <T extends { job_id: number }>(handler: (payload: T) => void) =>
+      (e: { payload: T }) => {
+        if (pending) {
+          pending.push({ job_id: e.payload.job_id, run: () => handler(e.payload) });
+        } else if (e.payload.job_id === jobId.current) {
+          handler(e.payload);
+        }
+      };
 
     try {
-      const u1 = await listen<string>("img-start", (e) =>
-        setStatusMap((p) => ({ ...p, [e.payload]: "processing" })),
+      const u1 = await listen<{ job_id: number; path: string }>(
+        "img-start",
+        forJob((payload) => setStatusMap((p) => ({ ...p, [payload.path]: "processing" }))),
       );
       unlisteners.current.push(u1);
 
-      const u2 = await listen<ProcessResult>("img-processed", (e) => {
+      const u2 = await listen<ProcessResult>("img-processed", forJob((payload) => {
+        // Une image abandonnée par l'annulation redevient "idle"
+        const status: FileStatus =
+          payload.status === "cancelled"
+            ? "idle"
+            : payload.status.startsWith("skipped")
+              ? "skipped"
+              : (payload.status as FileStatus);
         setStatusMap((p) => ({
           ...p,
-          [e.payload.original]: e.payload.status as FileStatus,
+          [payload.original]: status,
         }));
-        if (e.payload.status === "success") {
+        if (payload.warning) {
+          console.warn(`${payload.original}: ${payload.warning}`);
+        }
+        if (status === "skipped") {
           setProcessedCount((p) => p + 1);
         }
-      });
+        if (payload.status === "success") {
+          setProcessedCount((p) => p + 1);
+          // Tailles réelles à la place de l'estimation
+          const { original, original_size, output_size } = payload;
+          if (original_size !== null && output_size !== null) {
+            setFiles((current) =>
+              current.map((f) =>
+                f.path === original
+                  ? {
+                      ...f,
+                      originalSize: original_size,
+                      previewSize: output_size,
+                    }
+                  : f,
+              ),
+            );
+          }
+        }
+      }));
       unlisteners.current.push(u2);
 
-      const u3 = await listen("batch-finished", () => {
-        setIsProcessing(false);
-        setIsSuccess(true);
-        cleanupListeners();
-      });
+      const u3 = await listen<{ job_id: number }>(
+        "batch-finished",
+        forJob(() => {
+          setIsProcessing(false);
+          setIsSuccess(true);
+          cleanupListeners();
+        }),
+      );
       unlisteners.current.push(u3);
 
-      await invoke("compress_images", { config });
+      const u4 = await listen<{ job_id: number; done: ProcessResult[] }>(
+        "batch-cancelled",
+        forJob(() => {
+          setIsProcessing(false);
+          cleanupListeners();
+        }),
+      );
+      unlisteners.current.push(u4);
+
+      const u5 = await listen<{ job_id: number; paused: boolean }>(
+        "batch-paused",
+        forJob((payload) => setIsPaused(payload.paused)),
+      );
+      unlisteners.current.push(u5);
+
+      const id = await submit();
+      jobId.current = id;
+      const buffered = pending;
+      pending = null;
+      buffered.filter((event) => event.job_id === id).forEach((event) => event.run());
     } catch (error) {
+      pending = null;
       console.error(error);
       setIsProcessing(false);
       cleanupListeners();
     }
   };
 
+
+  const startCompression = async () => {
+    if (files.length === 0 || !outputDir) return;
+
+    const config: CompressConfig = {
+      paths: files.map((f) => f.path),
+      output_dir: outputDir,
+      format,
+      quality: Number(quality),
+      max_width: maxWidth ? Number(maxWidth) : null,
+      max_height: maxHeight ? Number(maxHeight) : null,
+      lossless: lossless && format !== "jpg",
+      target_size:
+        targetSizeKb && !lossless ? Math.round(Number(targetSizeKb) * 1024) : null,
+      min_ssim:
+        minSsim && !targetSizeKb && !lossless && format !== "jxl"
+          ? Number(minSsim)
+          : null,
+      prefix: null,
+      suffix: null,
+      custom_names: null,
+      background,
+      if_larger: ifLarger,
+      min_savings_percent: minSavingsPercent ? Number(minSavingsPercent) : null,
+      min_savings_bytes: minSavingsKb ? Math.round(Number(minSavingsKb) * 1024) : null,
+      copy_skipped: copySkipped,
+      avif,
+      webp,
+      jpeg,
+      jxl: { effort: jxlEffort },
+    };
+
+    await runJob(() => invoke<number>("compress_images", { config }));
+  };
+
   const totalSaved = files.reduce((acc, file) => {
     if (
       statusMap[file.path] === "success" &&
@@ -524,6 +805,8 @@ function App() {
             <option value="avif">AVIF</option>
             <option value="jpg">JPEG</option>
             <option value="png">PNG</option>
+            <option value="jxl">JPEG XL</option>
+            <option value="auto">Auto (smallest)</option>
           </select>
         </div>
 
@@ -537,10 +820,227 @@ function App() {
             min="10"
             max="100"
             value={quality}
+            disabled={lossless && format !== "jpg"}
             onChange={(e) => setQuality(Number(e.target.value))}
           />
+          {format !== "jpg" && (
+            <label style={{ display: "flex", alignItems: "center", gap: 8 }}>
+              <input
+                type="checkbox"
+                checked={lossless}
+                onChange={(e) => setLossless(e.target.checked)}
+              />
+              Lossless
+            </label>
+          )}
         </div>
 
+        {(format === "avif" || format === "auto") && (
+          <div className="control-group">
+            <div className="flex-between">
+              <label className="label-title">AVIF Speed</label>
+              <span className="value-display">{avif.speed}</span>
+            </div>
+            <input
+              type="range"
+              min="1"
+              max="10"
+              value={avif.speed}
+              onChange={(e) =>
+                setAvif({ ...avif, speed: Number(e.target.value) })
+              }
+            />
+            <input
+              type="number"
+              min="0"
+              max="100"
+              placeholder="Alpha quality (auto)"
+              value={avif.alpha_quality ?? ""}
+              onChange={(e) =>
+                setAvif({
+                  ...avif,
+                  alpha_quality: e.target.value ? Number(e.target.value) : null,
+                })
+              }
+            />
+            <div className="dimensions-grid">
+              <select
+                value={avif.bit_depth ?? ""}
+                onChange={(e) =>
+                  setAvif({
+                    ...avif,
+                    bit_depth: (e.target.value || null) as AvifSettings["bit_depth"],
+                  })
+                }
+              >
+                <option value="">Depth: auto</option>
+                <option value="8">8-bit</option>
+                <option value="10">10-bit</option>
+              </select>
+              <select
+                value={avif.color_model}
+                onChange={(e) =>
+                  setAvif({
+                    ...avif,
+                    color_model: e.target.value as AvifSettings["color_model"],
+                  })
+                }
+              >
+                <option value="ycbcr">YCbCr</option>
+                <option value="rgb">RGB</option>
+              </select>
+            </div>
+            <select
+              value={avif.alpha_mode}
+              onChange={(e) =>
+                setAvif({
+                  ...avif,
+                  alpha_mode: e.target.value as AvifSettings["alpha_mode"],
+                })
+              }
+            >
+              <option value="clean">Transparent pixels: clean</option>
+              <option value="dirty">Transparent pixels: keep colors</option>
+              <option value="premultiplied">Premultiplied alpha</option>
+            </select>
+          </div>
+        )}
+
+        {(format === "webp" || format === "auto") && (
+          <div className="control-group">
+            <div className="flex-between">
+              <label className="label-title">WebP Effort</label>
+              <span className="value-display">{webp.method}</span>
+            </div>
+            <input
+              type="range"
+              min="0"
+              max="6"
+              value={webp.method}
+              onChange={(e) =>
+                setWebp({ ...webp, method: Number(e.target.value) })
+              }
+            />
+            <div className="dimensions-grid">
+              <input
+                type="number"
+                min="0"
+                max="100"
+                placeholder="Alpha quality"
+                value={webp.alpha_quality}
+                onChange={(e) =>
+                  setWebp({ ...webp, alpha_quality: Number(e.target.value) })
+                }
+              />
+              <input
+                type="number"
+                min="0"
+                max="100"
+                placeholder="Near-lossless (off)"
+                value={webp.near_lossless ?? ""}
+                onChange={(e) =>
+                  setWebp({
+                    ...webp,
+                    near_lossless: e.target.value ? Number(e.target.value) : null,
+                  })
+                }
+              />
+            </div>
+            <label style={{ display: "flex", alignItems: "center", gap: 8 }}>
+              <input
+                type="checkbox"
+                checked={webp.sharp_yuv}
+                onChange={(e) =>
+                  setWebp({ ...webp, sharp_yuv: e.target.checked })
+                }
+              />
+              Sharp YUV (crisp red text)
+            </label>
+            <label style={{ display: "flex", alignItems: "center", gap: 8 }}>
+              <input
+                type="checkbox"
+                checked={webp.exact}
+                onChange={(e) => setWebp({ ...webp, exact: e.target.checked })}
+              />
+              Keep colors under transparency
+            </label>
+          </div>
+        )}
+
+        {format === "jxl" && (
+          <div className="control-group">
+            <div className="flex-between">
+              <label className="label-title">JPEG XL Effort</label>
+              <span className="value-display">{jxlEffort}</span>
+            </div>
+            <input
+              type="range"
+              min="1"
+              max="9"
+              value={jxlEffort}
+              onChange={(e) => setJxlEffort(Number(e.target.value))}
+            />
+          </div>
+        )}
+
+        {(format === "jpg" || format === "auto") && (
+          <div className="control-group">
+            <label className="label-title">JPEG Chroma Subsampling</label>
+            <select
+              value={jpeg.subsampling}
+              onChange={(e) =>
+                setJpeg({
+                  ...jpeg,
+                  subsampling: e.target.value as JpegSettings["subsampling"],
+                })
+              }
+            >
+              <option value="420">4:2:0 (smallest)</option>
+              <option value="422">4:2:2</option>
+              <option value="444">4:4:4 (sharp colors)</option>
+            </select>
+            <label style={{ display: "flex", alignItems: "center", gap: 8 }}>
+              <input
+                type="checkbox"
+                checked={jpeg.progressive}
+                onChange={(e) =>
+                  setJpeg({
+                    ...jpeg,
+                    progressive: e.target.checked,
+                    // Baseline : profil rapide de mozjpeg, sans trellis
+                    trellis: e.target.checked && jpeg.trellis,
+                    // Le progressif optimise toujours les tables de Huffman
+                    optimize_huffman: e.target.checked || jpeg.optimize_huffman,
+                  })
+                }
+              />
+              Progressive
+            </label>
+            <label style={{ display: "flex", alignItems: "center", gap: 8 }}>
+              <input
+                type="checkbox"
+                checked={jpeg.trellis}
+                disabled={!jpeg.progressive}
+                onChange={(e) =>
+                  setJpeg({ ...jpeg, trellis: e.target.checked })
+                }
+              />
+              Trellis quantization
+            </label>
+            <label style={{ display: "flex", alignItems: "center", gap: 8 }}>
+              <input
+                type="checkbox"
+                checked={jpeg.optimize_huffman}
+                disabled={jpeg.progressive}
+                onChange={(e) =>
+                  setJpeg({ ...jpeg, optimize_huffman: e.target.checked })
+                }
+              />
+              Optimized Huffman tables
+            </label>
+          </div>
+        )}
+
         <div className="control-group">
           <label className="label-title">Dimensions (Max)</label>
           <div className="dimensions-grid">
@@ -559,6 +1059,89 @@ function App() {
           </div>
         </div>
 
+        <div className="control-group">
+          <label className="label-title">Max File Size (KB)</label>
+          <input
+            type="number"
+            placeholder="No limit"
+            value={targetSizeKb}
+            onChange={(e) => setTargetSizeKb(e.target.value)}
+          />
+        </div>
+
+        <div className="control-group">
+          <label className="label-title">Min Visual Quality (SSIM)</label>
+          <input
+            type="number"
+            step="0.005"
+            min="0"
+            max="1"
+            placeholder="e.g. 0.98"
+            value={minSsim}
+            disabled={!!targetSizeKb || format === "jxl"}
+            onChange={(e) => setMinSsim(e.target.value)}
+          />
+        </div>
+
+        <div className="control-group">
+          <label className="label-title">If Output Is Larger</label>
+          <select
+            value={ifLarger}
+            onChange={(e) =>
+              setIfLarger(e.target.value as typeof ifLarger)
+            }
+            className="select-big"
+          >
+            <option value="keep">Keep output</option>
+            <option value="copy">Copy original</option>
+            <option value="skip">Skip file</option>
+            <option value="retry">Retry lower quality</option>
+          </select>
+        </div>
+
+        <div className="control-group">
+          <label className="label-title">Min Savings</label>
+          <div className="dimensions-grid">
+            <input
+              type="number"
+              min="0"
+              max="100"
+              placeholder="%"
+              value={minSavingsPercent}
+              onChange={(e) => setMinSavingsPercent(e.target.value)}
+            />
+            <input
+              type="number"
+              min="0"
+              placeholder="KB"
+              value={minSavingsKb}
+              onChange={(e) => setMinSavingsKb(e.target.value)}
+            />
+          </div>
+          <label style={{ display: "flex", alignItems: "center", gap: 8 }}>
+            <input
+              type="checkbox"
+              checked={copySkipped}
+              onChange={(e) => setCopySkipped(e.target.checked)}
+            />
+            Copy original when skipped
+          </label>
+        </div>
+
+        <div className="control-group">
+          <label
+            className="label-title"
+            style={{ display: "flex", alignItems: "center", gap: 8 }}
+          >
+            <input
+              type="checkbox"
+              checked={background}
+              onChange={(e) => setBackground(e.target.checked)}
+            />
+            Background mode (low CPU)
+          </label>
+        </div>
+
         <div style={{ marginTop: "auto", width: "100%" }}>
           <button
             className="btn-primary"
@@ -619,6 +1202,32 @@ function App() {
               Open Output Folder
             </button>
           )}
+          {isSuccess && (
+            <div style={{ display: "flex", gap: 10, justifyContent: "center" }}>
+              {(["csv", "json"] as const).map((reportFormat) => (
+                <button
+                  key={reportFormat}
+                  onClick={() =>
+                    invoke("export_report", {
+                      jobId: jobId.current,
+                      format: reportFormat,
+                    })
+                  }
+                  style={{
+                    background: "none",
+                    border: "none",
+                    color: "#64748b",
+                    textDecoration: "underline",
+                    cursor: "pointer",
+                    marginTop: 5,
+                    fontSize: "0.8rem",
+                  }}
+                >
+                  Export {reportFormat.toUpperCase()} report
+                </button>
+              ))}
+            </div>
+          )}
         </div>
 
         {isProcessing && files.length > 0 && (
@@ -634,6 +1243,36 @@ function App() {
             <div className="progress-text">
               {processedCount} / {files.length}
             </div>
+            <div style={{ display: "flex", gap: 10, marginTop: 10 }}>
+              <button
+                className="btn-secondary"
+                onClick={() =>
+                  invoke(isPaused ? "resume_batch" : "pause_batch", {
+                    jobId: jobId.current,
+                  })
+                }
+                style={{ flex: 1 }}
+              >
+                {isPaused ? (
+                  <>
+                    <Play size={18} /> Resume
+                  </>
+                ) : (
+                  <>
+                    <Pause size={18} /> Pause
+                  </>
+                )}
+              </button>
+              <button
+                className="btn-secondary"
+                onClick={() =>
+                  invoke("cancel_batch", { jobId: jobId.current })
+                }
+                style={{ flex: 1 }}
+              >
+                <X size={18} /> Cancel
+              </button>
+            </div>
           </div>
         )}
       </aside>